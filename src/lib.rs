@@ -1,9 +1,17 @@
 #![forbid(unsafe_code)]
 
+use base64::alphabet::Alphabet;
+use base64::engine::general_purpose::NO_PAD;
+use base64::engine::GeneralPurpose;
 use base64::prelude::{BASE64_STANDARD_NO_PAD, BASE64_URL_SAFE_NO_PAD};
-use base64::Engine;
+use base64::{DecodeSliceError, Engine};
 use crc::Crc;
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Read};
+use std::ops::{BitAnd, BitOr, BitOrAssign};
 use std::str::FromStr;
 
 pub type Workchain = i32;
@@ -23,21 +31,375 @@ pub const BASE64_URL_DEFAULT: Base64Encoder = Base64Encoder::UrlSafe {
     production: true,
 };
 
+/// A quick alias for converting an [`Address`] structure to
+/// a Base64 Standard string representation of a bounceable testnet address.
+pub const BASE64_STD_TESTNET: Base64Encoder = Base64Encoder::Standard {
+    bounceable: true,
+    production: false,
+};
+
+/// A quick alias for converting an [`Address`] structure to
+/// a Base64 Url Safe string representation of a bounceable testnet address.
+pub const BASE64_URL_TESTNET: Base64Encoder = Base64Encoder::UrlSafe {
+    bounceable: true,
+    production: false,
+};
+
+/// A quick alias for converting an [`Address`] structure to
+/// a Base64 Standard string representation of a non-bounceable testnet address.
+pub const BASE64_STD_TESTNET_NON_BOUNCEABLE: Base64Encoder = Base64Encoder::Standard {
+    bounceable: false,
+    production: false,
+};
+
+/// A quick alias for converting an [`Address`] structure to
+/// a Base64 Url Safe string representation of a non-bounceable testnet address.
+pub const BASE64_URL_TESTNET_NON_BOUNCEABLE: Base64Encoder = Base64Encoder::UrlSafe {
+    bounceable: false,
+    production: false,
+};
+
+/// Reason of the error returned for a Base64 form containing non-ASCII input.
+const NON_ASCII_REASON: &str =
+    "Invalid base64 address string: address contains non-ASCII characters";
+
 #[inline]
 fn crc16(slice: &[u8]) -> u16 {
     Crc::<u16>::new(&crc::CRC_16_XMODEM).checksum(slice)
 }
 
 #[derive(Debug, thiserror::Error, PartialEq)]
-#[error("Error parsing TON address: {reason}")]
+#[error(
+    "Error parsing TON address: {reason}{}",
+    .offset.map(|offset| format!(" (at byte offset {offset})")).unwrap_or_default()
+)]
 pub struct ParseError {
     pub address: String,
     pub reason: &'static str,
+    /// The offset of the byte that caused the error in the decoded address, if known.
+    /// For the stream errors of [`Address::parse_reader`], the 1-based line number.
+    pub offset: Option<usize>,
+}
+
+/// A warning about an address that is valid but likely used by mistake,
+/// see [`Address::to_base64_checked`].
+#[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
+pub enum AddressWarning {
+    /// The hash part is all zeros, see [`Address::is_zero`].
+    #[error("Warning encoding TON address: {encoded} is the null address")]
+    NullAddress {
+        /// The encoded address, so the caller can still proceed.
+        encoded: String,
+    },
+}
+
+/// A workchain number that is guaranteed to fit into the single signed byte
+/// used by the Base64 representation of an address.
+///
+/// Unlike the [`Workchain`] alias, an invalid value cannot be constructed,
+/// so it will never be silently truncated during encoding.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WorkchainId(i8);
+
+impl WorkchainId {
+    /// The masterchain, workchain number `-1`.
+    pub const MASTERCHAIN: WorkchainId = WorkchainId(-1);
+
+    /// The basechain, workchain number `0`.
+    pub const BASECHAIN: WorkchainId = WorkchainId(0);
+
+    /// Creates a new [`WorkchainId`] if the `workchain` argument is in the
+    /// `-128..=127` range, otherwise returns `None`.
+    pub fn new(workchain: Workchain) -> Option<Self> {
+        i8::try_from(workchain).ok().map(Self)
+    }
+
+    /// Returns the number of the workchain.
+    pub fn get(&self) -> Workchain {
+        self.0 as Workchain
+    }
+}
+
+impl FromStr for WorkchainId {
+    type Err = ParseError;
+
+    /// Parses the workchain number and checks that it fits into the `-128..=127` range.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let workchain = match s.parse::<Workchain>() {
+            Ok(wc) => wc,
+            Err(_) => {
+                return Err(ParseError {
+                    address: s.to_owned(),
+                    reason: "Invalid workchain string: workchain number is not a 32-bit integer",
+                    offset: None,
+                });
+            }
+        };
+
+        WorkchainId::new(workchain).ok_or_else(|| ParseError {
+            address: s.to_owned(),
+            reason: "Invalid workchain string: workchain number must be in range -128..=127",
+            offset: None,
+        })
+    }
+}
+
+impl Display for WorkchainId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// A set of flags stored in the tag byte of a Base64 address.
+///
+/// The flags can be combined with the `|` operator, e.g.
+/// `AddressFlags::BOUNCEABLE | AddressFlags::TESTNET`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AddressFlags(u8);
+
+impl AddressFlags {
+    /// The address is bounceable.
+    pub const BOUNCEABLE: AddressFlags = AddressFlags(0b01);
+
+    /// The address is intended for the test network only.
+    pub const TESTNET: AddressFlags = AddressFlags(0b10);
+
+    /// Non-bounceable mainnet address, i.e. no flags are set.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns the raw bits of the flags.
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// Returns `true` if all the flags in `other` are set.
+    pub const fn contains(&self, other: AddressFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `true` if the [`AddressFlags::BOUNCEABLE`] flag is set.
+    pub const fn is_bounceable(&self) -> bool {
+        self.contains(Self::BOUNCEABLE)
+    }
+
+    /// Returns `true` if the [`AddressFlags::TESTNET`] flag is set.
+    pub const fn is_testnet(&self) -> bool {
+        self.contains(Self::TESTNET)
+    }
+
+    /// Creates flags from the `bounceable` and `production` booleans
+    /// used across the [`Base64Encoder`] variants.
+    pub const fn from_bools(bounceable: bool, production: bool) -> Self {
+        let mut bits = 0;
+
+        if bounceable {
+            bits |= Self::BOUNCEABLE.0;
+        }
+
+        if !production {
+            bits |= Self::TESTNET.0;
+        }
+
+        Self(bits)
+    }
+
+    /// Converts the tag byte of an address into flags.
+    ///
+    /// Returns `None` if the byte is not one of `0x11`, `0x51`, `0x91` or `0xD1`.
+    pub const fn from_tag_byte(tag: u8) -> Option<Self> {
+        match tag {
+            0x11 => Some(Self(Self::BOUNCEABLE.0)),
+            0x51 => Some(Self(0)),
+            0x91 => Some(Self(Self::BOUNCEABLE.0 | Self::TESTNET.0)),
+            0xD1 => Some(Self(Self::TESTNET.0)),
+            _ => None,
+        }
+    }
+
+    /// Converts the flags into the tag byte of an address.
+    pub const fn to_tag_byte(&self) -> u8 {
+        let mut tag = 0x11;
+
+        if !self.is_bounceable() {
+            tag |= 0x40;
+        }
+
+        if self.is_testnet() {
+            tag |= 0x80;
+        }
+
+        tag
+    }
+}
+
+impl BitOr for AddressFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for AddressFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for AddressFlags {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+
+/// The string format of an address, see [`detect_format`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AddressFormat {
+    /// The raw address, e.g. “0:fa16bc...”.
+    Raw,
+
+    /// The Base64 address using the standard alphabet.
+    Base64Standard,
+
+    /// The Base64 address using the url safe alphabet.
+    Base64UrlSafe,
+
+    /// Neither raw nor Base64.
+    Unknown,
+}
+
+/// The network the address is intended for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+/// Whether messages sent to the address should bounce on failure.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Bounce {
+    Bounceable,
+    NonBounceable,
+}
+
+/// The type of a Base64 address defined by its tag byte.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AddressType {
+    /// Bounceable mainnet address (`EQ...`, `Ef...`).
+    Bounceable,
+
+    /// Non-bounceable mainnet address (`UQ...`, `Uf...`).
+    NonBounceable,
+
+    /// Bounceable testnet address (`kQ...`, `kf...`).
+    TestnetBounceable,
+
+    /// Non-bounceable testnet address (`0Q...`, `0f...`).
+    TestnetNonBounceable,
+}
+
+impl AddressType {
+    /// Returns the type matching the given `flags`.
+    pub const fn from_flags(flags: AddressFlags) -> Self {
+        match (flags.is_bounceable(), flags.is_testnet()) {
+            (true, false) => Self::Bounceable,
+            (false, false) => Self::NonBounceable,
+            (true, true) => Self::TestnetBounceable,
+            (false, true) => Self::TestnetNonBounceable,
+        }
+    }
+
+    /// Returns the flags of the address type.
+    pub const fn flags(&self) -> AddressFlags {
+        match self {
+            Self::Bounceable => AddressFlags::BOUNCEABLE,
+            Self::NonBounceable => AddressFlags::empty(),
+            Self::TestnetBounceable => AddressFlags(0b11),
+            Self::TestnetNonBounceable => AddressFlags::TESTNET,
+        }
+    }
+
+    pub const fn is_bounceable(&self) -> bool {
+        self.flags().is_bounceable()
+    }
+
+    pub const fn is_testnet(&self) -> bool {
+        self.flags().is_testnet()
+    }
+
+    /// Returns the type of a Base64 address by checking only its length and tag byte.
+    ///
+    /// The CRC16 checksum is not validated, so the result can be used to warn
+    /// the user early, e.g. about a testnet address, but not to validate it.
+    /// Use [`AddressType::classify_strict`] for that.
+    pub fn classify(address: &str) -> Result<AddressType, ParseError> {
+        let address = address.trim_ascii();
+
+        if address.len() != 48 {
+            return Err(ParseError {
+                address: address.to_owned(),
+                reason: "Invalid base64 address string: length must be 48 characters",
+                offset: None,
+            });
+        }
+
+        // The first 4 characters are decoded into the first 3 bytes,
+        // which contain the tag byte.
+        let head = address.get(..4).unwrap_or_default();
+        let mut bytes = [0u8; 3];
+
+        if Base64Decoder::guess(address)
+            .engine()
+            .decode_slice(head, &mut bytes)
+            .is_err()
+        {
+            return Err(ParseError {
+                address: address.to_owned(),
+                reason: "Invalid base64 address string: base64 decode error",
+                offset: None,
+            });
+        }
+
+        match AddressFlags::from_tag_byte(bytes[0]) {
+            Some(flags) => Ok(AddressType::from_flags(flags)),
+            None => Err(ParseError {
+                address: address.to_owned(),
+                reason: "Invalid base64 address string: invalid flag",
+                offset: Some(0),
+            }),
+        }
+    }
+
+    /// Same as [`AddressType::classify`], but the address is fully validated
+    /// with [`Address::from_base64`].
+    pub fn classify_strict(address: &str) -> Result<AddressType, ParseError> {
+        Ok(AddressType::from_flags(
+            Address::from_base64(address, None)?.flags(),
+        ))
+    }
+}
+
+/// A codec converting the 36 bytes of an address to a string and back.
+///
+/// Implement it to reuse the tag and CRC16 handling of this crate with
+/// a non-standard alphabet, see [`Address::to_base64_with_codec`] and
+/// [`Address::from_base64_with_codec`]. The built-in alphabets are
+/// available through the [`Base64Decoder`] implementation.
+pub trait AddressCodec {
+    /// Encodes the 36 bytes of an address to a string.
+    fn encode(&self, buffer: &[u8; 36]) -> String;
+
+    /// Decodes a string to the bytes of an address.
+    fn decode(&self, s: &str) -> Result<Vec<u8>, ParseError>;
 }
 
 /// A decoder used to encrypt and decrypt Base64 addresses
 /// on The Open Network (TON).
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Base64Decoder {
     /// [`STANDARD`]: base64::alphabet::STANDARD
     /// [`NO_PAD`]: base64::engine::general_purpose::NO_PAD
@@ -50,22 +412,43 @@ pub enum Base64Decoder {
     ///
     /// Using the [`URL_SAFE`] base64 alphabet and [`NO_PAD`] config.
     UrlSafe,
+
+    /// [`NO_PAD`]: base64::engine::general_purpose::NO_PAD
+    ///
+    /// Using a custom base64 alphabet and [`NO_PAD`] config.
+    Custom(&'static Alphabet),
 }
 
 impl Base64Decoder {
+    /// Returns the base64 engine of the selected alphabet.
+    #[inline]
+    fn engine(&self) -> GeneralPurpose {
+        match self {
+            Self::Standard => BASE64_STANDARD_NO_PAD,
+            Self::UrlSafe => BASE64_URL_SAFE_NO_PAD,
+            Self::Custom(alphabet) => GeneralPurpose::new(alphabet, NO_PAD),
+        }
+    }
+
     /// Decodes a Base64 encoded string depending on the selected algorithm.
+    ///
+    /// The address is decoded directly into a fixed stack buffer, so no
+    /// allocation happens on the successful path.
     #[inline]
-    fn decode<'b: 'a, 'a>(&'a self, str: &'b str) -> Result<Vec<u8>, ParseError> {
-        let res = match self {
-            Self::Standard => BASE64_STANDARD_NO_PAD.decode(str),
-            Self::UrlSafe => BASE64_URL_SAFE_NO_PAD.decode(str),
-        };
+    fn decode_to_buffer<'b: 'a, 'a>(&'a self, str: &'b str) -> Result<[u8; 36], ParseError> {
+        let mut buffer = [0u8; 36];
 
-        match res {
-            Ok(v) => Ok(v),
-            Err(_) => Err(ParseError {
+        match self.engine().decode_slice(str, &mut buffer) {
+            Ok(36) => Ok(buffer),
+            Ok(_) | Err(DecodeSliceError::OutputSliceTooSmall) => Err(ParseError {
+                address: str.to_owned(),
+                reason: "Invalid base64 address string: length of decoded bytes must be 36",
+                offset: None,
+            }),
+            Err(DecodeSliceError::DecodeError(_)) => Err(ParseError {
                 address: str.to_owned(),
                 reason: "Invalid base64 address string: base64 decode error",
+                offset: None,
             }),
         }
     }
@@ -84,36 +467,209 @@ impl Base64Decoder {
         // So it's 100% safe.
         Base64Decoder::Standard
     }
+
+    /// Returns every built-in alphabet (standard and url safe) whose characters
+    /// are consistent with the `str` argument, so that each can be tried in turn.
+    ///
+    /// Unlike [`Base64Decoder::guess`], an alphanumeric string yields both
+    /// alphabets, and a string mixing the characters of both alphabets
+    /// (or containing other characters) yields none.
+    pub fn compatible_alphabets(str: &str) -> Vec<Base64Decoder> {
+        let mut alphabets = Vec::with_capacity(2);
+
+        if str
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+        {
+            alphabets.push(Base64Decoder::Standard);
+        }
+
+        if str
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        {
+            alphabets.push(Base64Decoder::UrlSafe);
+        }
+
+        alphabets
+    }
+}
+
+impl FromStr for Base64Decoder {
+    type Err = ParseError;
+
+    /// Parses the alphabet name: `standard`, `std`, `url`, `urlsafe` or `url-safe`
+    /// (case-insensitive). Custom alphabets can't be parsed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim_ascii().to_ascii_lowercase().as_str() {
+            "standard" | "std" => Ok(Base64Decoder::Standard),
+            "url" | "urlsafe" | "url-safe" => Ok(Base64Decoder::UrlSafe),
+            _ => Err(ParseError {
+                address: s.to_owned(),
+                reason: "Invalid alphabet string: unknown alphabet",
+                offset: None,
+            }),
+        }
+    }
+}
+
+impl Display for Base64Decoder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Standard => "standard",
+            Self::UrlSafe => "url-safe",
+            Self::Custom(_) => "custom",
+        })
+    }
+}
+
+impl AddressCodec for Base64Decoder {
+    fn encode(&self, buffer: &[u8; 36]) -> String {
+        self.engine().encode(buffer)
+    }
+
+    fn decode(&self, s: &str) -> Result<Vec<u8>, ParseError> {
+        self.engine().decode(s).map_err(|_| ParseError {
+            address: s.to_owned(),
+            reason: "Invalid base64 address string: base64 decode error",
+            offset: None,
+        })
+    }
 }
 
 /// An encoder that converts the Address structure to a Base64 string representation.
 #[derive(Debug, Copy, Clone)]
 pub enum Base64Encoder {
-    Standard { bounceable: bool, production: bool },
-    UrlSafe { bounceable: bool, production: bool },
+    Standard {
+        bounceable: bool,
+        production: bool,
+    },
+    UrlSafe {
+        bounceable: bool,
+        production: bool,
+    },
+    /// Uses a custom base64 alphabet with no padding, e.g. for systems that
+    /// can't store the `+`, `/`, `-` and `_` characters.
+    Custom {
+        alphabet: &'static Alphabet,
+        bounceable: bool,
+        production: bool,
+    },
+    /// **Non-standard.** Stores the full 32-bit workchain (big-endian) instead of
+    /// a single byte, for experimental chains whose workchain ids don't fit in an `i8`.
+    ///
+    /// The address is 39 bytes long (52 characters): tag, 4 workchain bytes,
+    /// hash part and CRC16 checksum. The tag is the standard one plus one
+    /// (`0x12`, `0x52`, `0x92` or `0xD2`), so regular decoders reject it.
+    ///
+    /// This representation is not canonical and is not understood by wallets,
+    /// explorers or any other TON software. Decode it with [`Address::from_base64_extended`].
+    /// Byte-level helpers such as [`Address::to_raw_bytes`] and [`Address::checksum`]
+    /// only know the standard 36-byte layout, so they return an error for this encoder.
+    Extended {
+        alphabet: Base64Decoder,
+        bounceable: bool,
+        production: bool,
+    },
 }
 
 impl Base64Encoder {
-    fn encode(&self, workchain: Workchain, hash_part: &HashPart) -> String {
-        let (bounceable, production) = match self {
+    /// Creates a [`Base64EncoderBuilder`] with the default settings:
+    /// standard alphabet, bounceable, mainnet.
+    pub const fn builder() -> Base64EncoderBuilder {
+        Base64EncoderBuilder::new()
+    }
+
+    /// Creates an encoder from the alphabet, the network and the bounce flag.
+    ///
+    /// Unlike the struct variants, the arguments can't be swapped by accident.
+    pub const fn new(alphabet: Base64Decoder, network: Network, bounce: Bounce) -> Self {
+        let bounceable = matches!(bounce, Bounce::Bounceable);
+        let production = matches!(network, Network::Mainnet);
+
+        match alphabet {
+            Base64Decoder::Standard => Self::Standard {
+                bounceable,
+                production,
+            },
+            Base64Decoder::UrlSafe => Self::UrlSafe {
+                bounceable,
+                production,
+            },
+            Base64Decoder::Custom(alphabet) => Self::Custom {
+                alphabet,
+                bounceable,
+                production,
+            },
+        }
+    }
+
+    /// Creates an encoder using the standard alphabet and the given `flags`.
+    pub const fn standard(flags: AddressFlags) -> Self {
+        Self::Standard {
+            bounceable: flags.is_bounceable(),
+            production: !flags.is_testnet(),
+        }
+    }
+
+    /// Creates an encoder using the url safe alphabet and the given `flags`.
+    pub const fn url_safe(flags: AddressFlags) -> Self {
+        Self::UrlSafe {
+            bounceable: flags.is_bounceable(),
+            production: !flags.is_testnet(),
+        }
+    }
+
+    /// Returns the flags that will be stored in the tag byte of the address.
+    pub const fn flags(&self) -> AddressFlags {
+        match *self {
             Self::Standard {
                 bounceable,
                 production,
-            } => (bounceable, production),
+            } => AddressFlags::from_bools(bounceable, production),
             Self::UrlSafe {
                 bounceable,
                 production,
-            } => (bounceable, production),
-        };
+            } => AddressFlags::from_bools(bounceable, production),
+            Self::Custom {
+                bounceable,
+                production,
+                ..
+            } => AddressFlags::from_bools(bounceable, production),
+            Self::Extended {
+                bounceable,
+                production,
+                ..
+            } => AddressFlags::from_bools(bounceable, production),
+        }
+    }
+
+    /// Returns the alphabet used by the encoder.
+    pub const fn alphabet(&self) -> Base64Decoder {
+        match *self {
+            Self::Standard { .. } => Base64Decoder::Standard,
+            Self::UrlSafe { .. } => Base64Decoder::UrlSafe,
+            Self::Custom { alphabet, .. } => Base64Decoder::Custom(alphabet),
+            Self::Extended { alphabet, .. } => alphabet,
+        }
+    }
+
+    /// Returns the tag byte (`0x11`, `0x51`, `0x91` or `0xD1`) that will be
+    /// written at the beginning of the address.
+    ///
+    /// The [`Base64Encoder::Extended`] encoder uses the standard tag plus one.
+    pub const fn tag_byte(&self) -> u8 {
+        match self {
+            Self::Extended { .. } => self.flags().to_tag_byte() + 1,
+            _ => self.flags().to_tag_byte(),
+        }
+    }
 
+    /// Builds the 36 bytes of an address: tag, workchain, hash part and CRC16.
+    fn build_buffer(&self, workchain: Workchain, hash_part: &HashPart) -> [u8; 36] {
         let mut buffer = [0u8; 36];
 
-        buffer[0] = match (bounceable, production) {
-            (true, true) => 0x11,
-            (true, false) => 0x51,
-            (false, true) => 0x91,
-            (false, false) => 0xD1,
-        };
+        buffer[0] = self.flags().to_tag_byte();
 
         buffer[1] = (workchain & 0xFF) as u8;
         buffer[2..34].clone_from_slice(hash_part);
@@ -123,559 +679,5927 @@ impl Base64Encoder {
         buffer[34] = ((crc >> 8) & 0xFF) as u8;
         buffer[35] = (crc & 0xFF) as u8;
 
-        match self {
-            Self::Standard { .. } => BASE64_STANDARD_NO_PAD.encode(buffer),
-            Self::UrlSafe { .. } => BASE64_URL_SAFE_NO_PAD.encode(buffer),
-        }
+        buffer
     }
-}
 
-/// An intermediate structure that should not be used explicitly,
-/// and represents the result of decoding an address through
-/// the [`Address`] structure.
-#[derive(Debug)]
-pub struct EncoderResult {
-    // TODO : eq
-    pub address: Address,
-    pub non_bounceable: bool,
-    pub non_production: bool,
-    #[allow(dead_code)]
-    pub decoder: Base64Decoder,
-}
+    /// Builds the 39 bytes of an extended address: tag, big-endian workchain,
+    /// hash part and CRC16, see [`Base64Encoder::Extended`].
+    fn build_extended_buffer(&self, workchain: Workchain, hash_part: &HashPart) -> [u8; 39] {
+        let mut buffer = [0u8; 39];
 
-impl EncoderResult {
-    pub fn is_non_bounceable(&self) -> bool {
-        self.non_bounceable
-    }
+        buffer[0] = self.tag_byte();
 
-    pub fn is_non_production(&self) -> bool {
-        self.non_production
-    }
+        buffer[1..5].clone_from_slice(&workchain.to_be_bytes());
+        buffer[5..37].clone_from_slice(hash_part);
 
-    pub fn is_bounceable(&self) -> bool {
-        !self.non_bounceable
-    }
+        let crc = crc16(&buffer[0..37]);
 
-    pub fn is_production(&self) -> bool {
-        !self.non_production
+        buffer[37] = ((crc >> 8) & 0xFF) as u8;
+        buffer[38] = (crc & 0xFF) as u8;
+
+        buffer
     }
-}
 
-impl PartialEq for EncoderResult {
-    /// The logic of the comparison in this case is such that regardless of the bounceable and
-    /// production, encoder flags, the result will be positive only if the workchain and
-    /// hash_part of both addresses are equal.
-    fn eq(&self, other: &Self) -> bool {
-        self.address == other.address
-    }
-}
-
-/// A structure representing the internals of an address
-/// in a Ton network.
-///
-/// Regardless of the address type, its `workchain` and `hash_part`
-/// always remain the same.
-#[derive(Debug, PartialEq)]
-pub struct Address {
-    // TODO : eq
-    workchain: Workchain,
-    hash_part: HashPart,
-}
+    fn encode(&self, workchain: Workchain, hash_part: &HashPart) -> String {
+        if let Self::Extended { .. } = self {
+            let buffer = self.build_extended_buffer(workchain, hash_part);
 
-impl Address {
-    /// Creates a new [`Address`] structure from workchain and hash_part.
-    pub fn new(workchain: Workchain, hash_part: &HashPart) -> Self {
-        Self {
-            workchain,
-            hash_part: *hash_part,
+            return self.alphabet().engine().encode(buffer);
         }
-    }
 
-    /// Creates a new [`Address`] structure using the null values of workchain
-    /// and hash_part.
-    pub fn empty() -> Self {
-        Self {
-            workchain: 0,
-            hash_part: [0u8; 32],
-        }
-    }
+        let buffer = self.build_buffer(workchain, hash_part);
 
-    /// Returns the number of the workchain.
-    pub fn get_workchain(&self) -> i32 {
-        self.workchain
+        self.alphabet().engine().encode(buffer)
     }
 
-    /// Returns a reference to the hash part.
-    pub fn get_hash_part(&self) -> &HashPart {
-        &self.hash_part
-    }
+    /// Encodes the address into a fixed stack buffer of ASCII characters
+    /// and passes the encoded characters to `f`.
+    fn encode_with<R>(
+        &self,
+        workchain: Workchain,
+        hash_part: &HashPart,
+        f: impl FnOnce(&[u8]) -> R,
+    ) -> R {
+        let mut output = [0u8; 52];
 
-    /// Attempt to create an [`Address`] structure from the
-    /// string representation of the raw address.
-    pub fn from_raw_address(str: &str) -> Result<Self, ParseError> {
-        let parts = str.split(':').collect::<Vec<&str>>();
+        let len = if let Self::Extended { .. } = self {
+            let buffer = self.build_extended_buffer(workchain, hash_part);
 
-        if parts.len() != 2 {
-            return Err(ParseError {
-                address: str.to_owned(),
-                reason: "Invalid raw address string: wrong address format",
-            });
-        }
+            self.alphabet()
+                .engine()
+                .encode_slice(buffer, &mut output)
+                .expect("39 bytes are always encoded to 52 characters without padding")
+        } else {
+            let buffer = self.build_buffer(workchain, hash_part);
 
-        let wc = match parts[0].parse::<i32>() {
-            Ok(wc) => wc,
-            Err(_) => {
-                return Err(ParseError {
-                    address: str.to_owned(),
-                    reason: "Invalid raw address string: workchain number is not a 32-bit integer",
-                });
-            }
+            self.alphabet()
+                .engine()
+                .encode_slice(buffer, &mut output)
+                .expect("36 bytes are always encoded to 48 characters without padding")
         };
 
-        let hash_part = match hex::decode(parts[1]) {
-            Ok(part) => part,
+        f(&output[..len])
+    }
+}
+
+impl FromStr for Base64Encoder {
+    type Err = ParseError;
+
+    /// Parses the encoder from an alphabet name (see [`Base64Decoder`]) followed by
+    /// optional colon separated flags, e.g. `url:non-bounceable:testnet`.
+    ///
+    /// The accepted flags are `bounceable`, `non-bounceable`, `mainnet`, `production`
+    /// and `testnet` (case-insensitive). The encoder is bounceable and mainnet
+    /// unless specified otherwise.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split(':');
+
+        let mut builder = match tokens.next().unwrap_or_default().parse::<Base64Decoder>() {
+            Ok(Base64Decoder::UrlSafe) => Base64Encoder::builder().url_safe(),
+            Ok(_) => Base64Encoder::builder().standard(),
             Err(_) => {
                 return Err(ParseError {
-                    address: str.to_owned(),
-                    reason: "Invalid raw address string: failed to decode hash part",
+                    address: s.to_owned(),
+                    reason: "Invalid encoder string: unknown alphabet",
+                    offset: None,
                 });
             }
         };
 
-        if hash_part.len() != 32 {
-            return Err(ParseError {
-                address: str.to_owned(),
-                reason: "Invalid raw address string: hash part length must be 32 bytes",
-            });
+        for token in tokens {
+            builder = match token.trim_ascii().to_ascii_lowercase().as_str() {
+                "bounceable" => builder.bounceable(),
+                "non-bounceable" => builder.non_bounceable(),
+                "mainnet" | "production" => builder.mainnet(),
+                "testnet" => builder.testnet(),
+                _ => {
+                    return Err(ParseError {
+                        address: s.to_owned(),
+                        reason: "Invalid encoder string: unknown flag",
+                        offset: None,
+                    });
+                }
+            };
         }
 
-        Ok(Self {
-            workchain: wc,
-            hash_part: hash_part.as_slice().try_into().expect(
-                "checking for hash part length ensures that the slice is safely cast to an array",
-            ),
-        })
+        Ok(builder.build())
     }
+}
 
-    /// Decodes the base64 address of the Ton network into an [`Address`] structure.
-    ///
-    /// If the `encoder` argument is specified, the method decodes the address “strictly”
-    /// according to the specified algorithm.
-    /// Otherwise, the address algorithm will be guessed by the presence of base64 control
-    /// characters.
-    pub fn from_base64(
-        address: &str,
-        encoder: Option<Base64Decoder>,
-    ) -> Result<EncoderResult, ParseError> {
-        if address.len() != 48 {
-            return Err(ParseError {
-                address: address.to_owned(),
-                reason: "Invalid base64 address string: length must be 48 characters",
-            });
-        }
-
-        let encoder = encoder.unwrap_or_else(|| Base64Decoder::guess(address));
-        let bytes = encoder.decode(address)?;
+impl Display for Base64Encoder {
+    /// Writes the alphabet and the flags of the encoder,
+    /// e.g. `url-safe (non-bounceable, testnet)`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let flags = self.flags();
 
-        if bytes.len() != 36 {
-            return Err(ParseError {
-                address: address.to_owned(),
-                reason: "Invalid base64 address string: length of decoded bytes must be 36",
-            });
+        if let Self::Extended { .. } = self {
+            f.write_str("extended ")?;
         }
 
-        let (non_production, non_bounceable) = match bytes[0] {
-            0x11 => (false, false),
-            0x51 => (false, true),
-            0x91 => (true, false),
-            0xD1 => (true, true),
-            _ => {
-                return Err(ParseError {
-                    address: address.to_owned(),
-                    reason: "Invalid base64 address string: invalid flag",
-                });
-            }
-        };
+        write!(
+            f,
+            "{} ({}, {})",
+            self.alphabet(),
+            if flags.is_bounceable() {
+                "bounceable"
+            } else {
+                "non-bounceable"
+            },
+            if flags.is_testnet() {
+                "testnet"
+            } else {
+                "mainnet"
+            },
+        )
+    }
+}
 
-        let workchain = bytes[1] as i32;
+/// A builder of the [`Base64Encoder`], see [`Base64Encoder::builder`].
+///
+/// By default it produces a standard alphabet, bounceable, mainnet encoder.
+#[derive(Debug, Copy, Clone)]
+pub struct Base64EncoderBuilder {
+    url_safe: bool,
+    bounceable: bool,
+    production: bool,
+}
 
-        let server_crc = crc16(&bytes[0..34]);
-        let client_crc = ((bytes[34] as u16) << 8) | (bytes[35] as u16);
+impl Default for Base64EncoderBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        if server_crc != client_crc {
-            return Err(ParseError {
-                address: address.to_owned(),
-                reason: "Invalid base64 address string: CRC16 hashes do not match",
-            });
+impl Base64EncoderBuilder {
+    /// Creates a builder with the default settings.
+    pub const fn new() -> Self {
+        Self {
+            url_safe: false,
+            bounceable: true,
+            production: true,
         }
+    }
 
-        let mut hash_part: HashPart = [0u8; 32];
-        hash_part.clone_from_slice(&bytes[2..34]);
+    /// Use the standard base64 alphabet.
+    pub const fn standard(mut self) -> Self {
+        self.url_safe = false;
+        self
+    }
 
-        Ok(EncoderResult {
-            address: Address {
-                workchain,
-                hash_part,
-            },
-            non_bounceable,
-            non_production,
-            decoder: encoder,
-        })
+    /// Use the url safe base64 alphabet.
+    pub const fn url_safe(mut self) -> Self {
+        self.url_safe = true;
+        self
     }
 
-    /// Converts the current structure to a string of the form “0:fa16bc...”
-    /// also known as the “raw address”.
-    pub fn to_raw_address(&self) -> String {
-        format!("{}:{}", self.workchain, hex::encode(self.hash_part))
+    /// Mark the address as bounceable.
+    pub const fn bounceable(mut self) -> Self {
+        self.bounceable = true;
+        self
     }
 
-    /// Converts the current structure to a Base64 string according to
-    /// the specified preferences in the `encoder` argument.
-    ///
-    /// Use the [`BASE64_STD_DEFAULT`] and [`BASE64_URL_DEFAULT`] constants for fast conversion.
-    pub fn to_base64(&self, encoder: Base64Encoder) -> String {
-        encoder.encode(self.workchain, &self.hash_part)
+    /// Mark the address as non-bounceable.
+    pub const fn non_bounceable(mut self) -> Self {
+        self.bounceable = false;
+        self
     }
-}
 
-impl FromStr for Address {
-    type Err = ParseError;
+    /// Mark the address as intended for the main network.
+    pub const fn mainnet(mut self) -> Self {
+        self.production = true;
+        self
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.contains(':') {
-            Address::from_raw_address(s)
+    /// Mark the address as intended for the test network.
+    pub const fn testnet(mut self) -> Self {
+        self.production = false;
+        self
+    }
+
+    /// Builds the [`Base64Encoder`].
+    pub const fn build(self) -> Base64Encoder {
+        if self.url_safe {
+            Base64Encoder::UrlSafe {
+                bounceable: self.bounceable,
+                production: self.production,
+            }
         } else {
-            Ok(Address::from_base64(s, None)?.address)
+            Base64Encoder::Standard {
+                bounceable: self.bounceable,
+                production: self.production,
+            }
         }
     }
 }
 
-impl TryFrom<String> for Address {
-    type Error = ParseError;
+/// Reusable encoding preferences, e.g. loaded from a configuration file
+/// and applied to many addresses, see [`Address::to_base64_with_options`].
+///
+/// The default options are url safe, bounceable and mainnet, i.e. [`BASE64_URL_DEFAULT`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EncodeOptions {
+    pub url_safe: bool,
+    pub bounceable: bool,
+    pub production: bool,
+}
 
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        if value.contains(':') {
-            Address::from_raw_address(&value)
-        } else {
-            Ok(Address::from_base64(&value, None)?.address)
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self {
+            url_safe: true,
+            bounceable: true,
+            production: true,
         }
     }
 }
 
-impl Display for Address {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.to_base64(BASE64_URL_DEFAULT).as_str())
+impl EncodeOptions {
+    /// Converts the options to the matching [`Base64Encoder`].
+    pub const fn to_encoder(&self) -> Base64Encoder {
+        if self.url_safe {
+            Base64Encoder::UrlSafe {
+                bounceable: self.bounceable,
+                production: self.production,
+            }
+        } else {
+            Base64Encoder::Standard {
+                bounceable: self.bounceable,
+                production: self.production,
+            }
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// An intermediate structure that should not be used explicitly,
+/// and represents the result of decoding an address through
+/// the [`Address`] structure.
+///
+/// The struct is `#[non_exhaustive]`: it is only meant to be produced by the
+/// parsing functions, so new fields can be added without breaking callers.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct EncoderResult {
+    pub address: Address,
+    pub non_bounceable: bool,
+    pub non_production: bool,
+    pub decoder: Base64Decoder,
+    /// Whether the address was decoded from the non-standard extended form,
+    /// see [`Base64Encoder::Extended`] and [`Address::from_base64_extended`].
+    pub extended: bool,
+}
 
-    #[test]
-    fn test_new_address() {
-        let bytes = hex::decode("e4d954ef9f4e1250a26b5bbad76a1cdd17cfd08babad6f4c23e372270aef6f76")
-            .unwrap();
-        let hash_part: HashPart = bytes.as_slice().try_into().unwrap();
-        let workchain = 0;
+impl EncoderResult {
+    /// Returns a reference to the decoded address.
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
 
-        let address = Address::new(workchain, &hash_part);
-        assert_eq!(address.get_workchain(), workchain);
-        assert_eq!(
-            address.get_hash_part(),
-            &[
-                0xe4, 0xd9, 0x54, 0xef, 0x9f, 0x4e, 0x12, 0x50, 0xa2, 0x6b, 0x5b, 0xba, 0xd7, 0x6a,
-                0x1c, 0xdd, 0x17, 0xcf, 0xd0, 0x8b, 0xab, 0xad, 0x6f, 0x4c, 0x23, 0xe3, 0x72, 0x27,
-                0x0a, 0xef, 0x6f, 0x76
-            ]
-        );
+    /// Consumes the result and returns the decoded address.
+    pub fn into_address(self) -> Address {
+        self.address
     }
 
-    #[test]
-    fn test_new_address_empty() {
-        let address = Address::empty();
+    pub fn is_non_bounceable(&self) -> bool {
+        self.non_bounceable
+    }
 
-        assert_eq!(address.get_workchain(), 0);
-        assert_eq!(address.get_hash_part(), &[0u8; 32]);
+    pub fn is_non_production(&self) -> bool {
+        self.non_production
     }
 
-    #[test]
-    fn test_new_address_from_raw_adress() {
-        // main case
-        {
-            let raw_address = "0:e4d954ef9f4e1250a26b5bbad76a1cdd17cfd08babad6f4c23e372270aef6f76";
-            let address = Address::from_raw_address(raw_address);
+    pub fn is_bounceable(&self) -> bool {
+        !self.non_bounceable
+    }
 
-            assert_eq!(
-                address,
-                Ok(Address::new(
-                    0,
-                    &[
-                        0xe4, 0xd9, 0x54, 0xef, 0x9f, 0x4e, 0x12, 0x50, 0xa2, 0x6b, 0x5b, 0xba,
-                        0xd7, 0x6a, 0x1c, 0xdd, 0x17, 0xcf, 0xd0, 0x8b, 0xab, 0xad, 0x6f, 0x4c,
-                        0x23, 0xe3, 0x72, 0x27, 0x0a, 0xef, 0x6f, 0x76
-                    ]
-                ))
-            );
-        }
+    pub fn is_production(&self) -> bool {
+        !self.non_production
+    }
 
-        // error cases
-        {
-            let raw_address = "bad_string";
-            let address = Address::from_raw_address(raw_address);
+    /// Returns the network the decoded address is intended for.
+    pub fn network(&self) -> Network {
+        if self.non_production {
+            Network::Testnet
+        } else {
+            Network::Mainnet
+        }
+    }
 
-            assert_eq!(
-                address,
-                Err(ParseError {
-                    address: raw_address.to_owned(),
-                    reason: "Invalid raw address string: wrong address format",
-                })
-            );
+    /// Returns whether the decoded address is bounceable.
+    pub fn bounce(&self) -> Bounce {
+        if self.non_bounceable {
+            Bounce::NonBounceable
+        } else {
+            Bounce::Bounceable
         }
+    }
 
-        {
-            let raw_address = "fdfd:fdfd";
-            let address = Address::from_raw_address(raw_address);
+    /// Returns the flags of the decoded address as a single value.
+    pub fn flags(&self) -> AddressFlags {
+        AddressFlags::from_bools(self.is_bounceable(), self.is_production())
+    }
 
-            assert_eq!(
-                address,
-                Err(ParseError {
-                    address: raw_address.to_owned(),
+    /// Returns the tag byte of the decoded address (`0x11`, `0x51`, `0x91` or `0xD1`).
+    pub fn tag_byte(&self) -> u8 {
+        self.flags().to_tag_byte()
+    }
+
+    /// Returns a human readable description of the flags stored in the tag byte,
+    /// e.g. `bounceable, mainnet` or `non-bounceable, testnet`.
+    ///
+    /// This is meant for UI hints and doesn't affect the string encoding.
+    pub fn describe(&self) -> String {
+        format!(
+            "{}, {}",
+            if self.is_bounceable() {
+                "bounceable"
+            } else {
+                "non-bounceable"
+            },
+            if self.is_production() {
+                "mainnet"
+            } else {
+                "testnet"
+            },
+        )
+    }
+
+    /// Compares both the address and the bounceable and production flags.
+    ///
+    /// Unlike `==`, which compares only the workchain and hash_part, this method
+    /// returns `true` only if both results would be encoded with the same tag byte.
+    pub fn eq_strict(&self, other: &Self) -> bool {
+        self.address == other.address
+            && self.non_bounceable == other.non_bounceable
+            && self.non_production == other.non_production
+    }
+
+    /// Changes the bounceable flag of the result.
+    pub fn set_bounceable(&mut self, bounceable: bool) {
+        self.non_bounceable = !bounceable;
+    }
+
+    /// Changes the production flag of the result.
+    pub fn set_production(&mut self, production: bool) {
+        self.non_production = !production;
+    }
+
+    /// Encodes the address back to a Base64 string using the current flags
+    /// and the alphabet the address was decoded with.
+    ///
+    /// An [extended](EncoderResult::extended) result is encoded back with
+    /// [`Base64Encoder::Extended`], so its full 32-bit workchain is kept.
+    pub fn to_base64(&self) -> String {
+        if self.extended {
+            return self.address.to_base64(Base64Encoder::Extended {
+                alphabet: self.decoder,
+                bounceable: self.is_bounceable(),
+                production: self.is_production(),
+            });
+        }
+
+        let encoder = match self.decoder {
+            Base64Decoder::Standard => Base64Encoder::standard(self.flags()),
+            Base64Decoder::UrlSafe => Base64Encoder::url_safe(self.flags()),
+            Base64Decoder::Custom(alphabet) => Base64Encoder::Custom {
+                alphabet,
+                bounceable: self.is_bounceable(),
+                production: self.is_production(),
+            },
+        };
+
+        self.address.to_base64(encoder)
+    }
+
+    /// Returns an opaque, self-describing token preserving the account, the flags
+    /// and the alphabet of the result, so that [`EncoderResult::to_base64`] of the
+    /// restored result gives back the exact original string.
+    ///
+    /// The token is the url safe Base64 form of: the alphabet (`0` standard,
+    /// `1` url safe, `2` custom followed by its 64 symbols at the end), the tag byte
+    /// (the extended one for [extended](EncoderResult::extended) results),
+    /// the big-endian 32-bit workchain and the hash part.
+    /// Use [`EncoderResult::from_token`] to restore it.
+    pub fn to_token(&self) -> String {
+        let mut buffer = Vec::with_capacity(38 + 64);
+
+        buffer.push(match self.decoder {
+            Base64Decoder::Standard => 0,
+            Base64Decoder::UrlSafe => 1,
+            Base64Decoder::Custom(_) => 2,
+        });
+        buffer.push(if self.extended {
+            self.flags().to_tag_byte() + 1
+        } else {
+            self.flags().to_tag_byte()
+        });
+        buffer.extend_from_slice(&self.address.workchain.to_be_bytes());
+        buffer.extend_from_slice(&self.address.hash_part);
+
+        if let Base64Decoder::Custom(alphabet) = self.decoder {
+            buffer.extend_from_slice(alphabet.as_str().as_bytes());
+        }
+
+        BASE64_URL_SAFE_NO_PAD.encode(buffer)
+    }
+
+    /// Restores the result from a token produced by [`EncoderResult::to_token`].
+    ///
+    /// Since [`Base64Decoder::Custom`] holds a `'static` alphabet, a custom alphabet
+    /// can only be restored if it is one of the alphabets of [`base64::alphabet`];
+    /// other custom alphabets are rejected.
+    pub fn from_token(token: &str) -> Result<EncoderResult, ParseError> {
+        let error = |reason| ParseError {
+            address: token.to_owned(),
+            reason,
+            offset: None,
+        };
+
+        let bytes = BASE64_URL_SAFE_NO_PAD
+            .decode(token)
+            .map_err(|_| error("Invalid token: base64 decode error"))?;
+
+        let decoder = match (bytes.first(), bytes.len()) {
+            (Some(0), 38) => Base64Decoder::Standard,
+            (Some(1), 38) => Base64Decoder::UrlSafe,
+            (Some(2), 102) => {
+                const KNOWN: [&Alphabet; 6] = [
+                    &base64::alphabet::STANDARD,
+                    &base64::alphabet::URL_SAFE,
+                    &base64::alphabet::CRYPT,
+                    &base64::alphabet::BCRYPT,
+                    &base64::alphabet::IMAP_MUTF7,
+                    &base64::alphabet::BIN_HEX,
+                ];
+
+                let symbols = &bytes[38..];
+
+                match KNOWN
+                    .into_iter()
+                    .find(|alphabet| alphabet.as_str().as_bytes() == symbols)
+                {
+                    Some(alphabet) => Base64Decoder::Custom(alphabet),
+                    None => return Err(error("Invalid token: unknown custom alphabet")),
+                }
+            }
+            (Some(0..=2), _) => return Err(error("Invalid token: invalid length")),
+            _ => return Err(error("Invalid token: invalid alphabet")),
+        };
+
+        let (flags, extended) = match AddressFlags::from_tag_byte(bytes[1]) {
+            Some(flags) => (flags, false),
+            None => match AddressFlags::from_tag_byte(bytes[1].wrapping_sub(1)) {
+                Some(flags) => (flags, true),
+                None => {
+                    return Err(ParseError {
+                        offset: Some(1),
+                        ..error("Invalid token: invalid flag")
+                    });
+                }
+            },
+        };
+
+        let mut workchain = [0u8; 4];
+        workchain.clone_from_slice(&bytes[2..6]);
+
+        let mut hash_part: HashPart = [0u8; 32];
+        hash_part.clone_from_slice(&bytes[6..38]);
+
+        Ok(EncoderResult {
+            address: Address {
+                workchain: Workchain::from_be_bytes(workchain),
+                hash_part,
+            },
+            non_bounceable: !flags.is_bounceable(),
+            non_production: flags.is_testnet(),
+            decoder,
+            extended,
+        })
+    }
+}
+
+impl PartialEq for EncoderResult {
+    /// The logic of the comparison in this case is such that regardless of the bounceable and
+    /// production, encoder flags, the result will be positive only if the workchain and
+    /// hash_part of both addresses are equal.
+    fn eq(&self, other: &Self) -> bool {
+        self.address == other.address
+    }
+}
+
+impl Eq for EncoderResult {}
+
+impl PartialEq<Address> for EncoderResult {
+    /// Compares the account identity only, like `PartialEq<EncoderResult>`.
+    fn eq(&self, other: &Address) -> bool {
+        self.address == *other
+    }
+}
+
+impl PartialEq<EncoderResult> for Address {
+    /// Compares the account identity only, the flags of the result are ignored.
+    fn eq(&self, other: &EncoderResult) -> bool {
+        *self == other.address
+    }
+}
+
+impl Hash for EncoderResult {
+    /// Consistent with [`PartialEq`], only the workchain and hash_part are hashed,
+    /// so results of the same account with different flags hash equally.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+    }
+}
+
+impl Borrow<Address> for EncoderResult {
+    /// Allows looking up a `HashMap<EncoderResult, V>` or a `HashSet<EncoderResult>`
+    /// by `&Address`. This is sound because [`PartialEq`] and [`Hash`] of the
+    /// result only take the address into account.
+    fn borrow(&self) -> &Address {
+        &self.address
+    }
+}
+
+impl TryFrom<&str> for EncoderResult {
+    type Error = ParseError;
+
+    /// Parses a Base64 address with its flags, see [`Address::from_base64`].
+    ///
+    /// A raw address (containing `:`) has no flags, so the result is bounceable,
+    /// mainnet and url safe, like the canonical form of the address.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = value.trim_ascii();
+
+        if value.contains(':') {
+            return Ok(EncoderResult {
+                address: Address::from_raw_address(value)?,
+                non_bounceable: false,
+                non_production: false,
+                decoder: Base64Decoder::UrlSafe,
+                extended: false,
+            });
+        }
+
+        Address::from_base64(value, None)
+    }
+}
+
+impl TryFrom<String> for EncoderResult {
+    type Error = ParseError;
+
+    /// Same as `TryFrom<&str>`.
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        EncoderResult::try_from(value.as_str())
+    }
+}
+
+/// A structure representing the internals of an address
+/// in a Ton network.
+///
+/// Regardless of the address type, its `workchain` and `hash_part`
+/// always remain the same.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow)
+)]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Text))]
+pub struct Address {
+    workchain: Workchain,
+    hash_part: HashPart,
+}
+
+impl Address {
+    /// Creates a new [`Address`] structure from workchain and hash_part.
+    pub fn new(workchain: Workchain, hash_part: &HashPart) -> Self {
+        Self {
+            workchain,
+            hash_part: *hash_part,
+        }
+    }
+
+    /// Same as [`Address::new`], but usable in `const` and `static` items,
+    /// e.g. to embed well-known addresses.
+    ///
+    /// No validation happens, the workchain is taken as is.
+    pub const fn from_raw_parts(workchain: Workchain, hash_part: HashPart) -> Self {
+        Self {
+            workchain,
+            hash_part,
+        }
+    }
+
+    /// Creates a new [`Address`] structure from an already validated workchain
+    /// and hash_part.
+    pub fn new_checked(workchain: WorkchainId, hash_part: &HashPart) -> Self {
+        Self::new(workchain.get(), hash_part)
+    }
+
+    /// Creates a new [`Address`] structure in the masterchain (workchain `-1`).
+    pub fn masterchain(hash_part: &HashPart) -> Self {
+        Self::new_checked(WorkchainId::MASTERCHAIN, hash_part)
+    }
+
+    /// Creates a new [`Address`] structure in the basechain (workchain `0`).
+    pub fn basechain(hash_part: &HashPart) -> Self {
+        Self::new_checked(WorkchainId::BASECHAIN, hash_part)
+    }
+
+    /// Creates a new [`Address`] structure using the null values of workchain
+    /// and hash_part.
+    pub fn empty() -> Self {
+        Self {
+            workchain: 0,
+            hash_part: [0u8; 32],
+        }
+    }
+
+    /// Checks whether the hash part is all zeros, like in [`Address::empty`],
+    /// regardless of the workchain.
+    pub fn is_zero(&self) -> bool {
+        self.hash_part == [0u8; 32]
+    }
+
+    /// Returns the number of the workchain.
+    pub fn get_workchain(&self) -> i32 {
+        self.workchain
+    }
+
+    /// Returns the account id, i.e. the hash part, as a big-endian 256-bit integer.
+    ///
+    /// This is the shape used by JSON-RPC APIs that identify an account by
+    /// a `u256` and keep the workchain separately.
+    pub fn account_id_be(&self) -> [u8; 32] {
+        self.hash_part
+    }
+
+    /// Returns the account id as a `0x`-prefixed lowercase hex string of 64 digits,
+    /// as expected by some JSON-RPC endpoints.
+    ///
+    /// See [`Address::from_account_id_hex`] for the reverse conversion.
+    pub fn account_id_hex_0x(&self) -> String {
+        format!("0x{}", self.hash_part_hex())
+    }
+
+    /// Creates a new [`Address`] structure from the workchain and the account id
+    /// as a hex string of 64 digits with an optional `0x` prefix.
+    pub fn from_account_id_hex(workchain: Workchain, hex: &str) -> Result<Self, ParseError> {
+        let digits = hex
+            .strip_prefix("0x")
+            .or_else(|| hex.strip_prefix("0X"))
+            .unwrap_or(hex);
+
+        let mut hash_part: HashPart = [0u8; 32];
+
+        if hex::decode_to_slice(digits, &mut hash_part).is_err() {
+            return Err(ParseError {
+                address: hex.to_owned(),
+                reason: "Invalid account id string: expected 64 hex digits",
+                offset: None,
+            });
+        }
+
+        Ok(Self::new(workchain, &hash_part))
+    }
+
+    /// Returns the workchain and the big-endian account id of the address.
+    ///
+    /// See [`Address::from_packed`] for the reverse conversion.
+    pub fn to_packed(&self) -> (Workchain, [u8; 32]) {
+        (self.workchain, self.account_id_be())
+    }
+
+    /// Creates a new [`Address`] structure from the workchain and
+    /// the big-endian account id, see [`Address::to_packed`].
+    pub fn from_packed(workchain: Workchain, account_id: [u8; 32]) -> Self {
+        Self::new(workchain, &account_id)
+    }
+
+    /// Destructures the address into its workchain and hash part.
+    ///
+    /// See [`Address::from_parts`] for the reverse conversion.
+    pub fn into_parts(self) -> (Workchain, HashPart) {
+        (self.workchain, self.hash_part)
+    }
+
+    /// Creates a new [`Address`] structure from a workchain and hash part tuple,
+    /// see [`Address::into_parts`].
+    pub fn from_parts((workchain, hash_part): (Workchain, HashPart)) -> Self {
+        Self {
+            workchain,
+            hash_part,
+        }
+    }
+
+    /// Returns a human readable name of the workchain: `masterchain` for `-1`,
+    /// `basechain` for `0` and `workchain N` for any other workchain.
+    pub fn workchain_label(&self) -> String {
+        match self.workchain {
+            -1 => "masterchain".to_owned(),
+            0 => "basechain".to_owned(),
+            n => format!("workchain {n}"),
+        }
+    }
+
+    /// Returns a reference to the hash part.
+    pub fn get_hash_part(&self) -> &HashPart {
+        &self.hash_part
+    }
+
+    /// Returns the hash part as a lowercase hex string of 64 characters.
+    pub fn hash_part_hex(&self) -> String {
+        hex::encode(self.hash_part)
+    }
+
+    /// Returns the hash part as an uppercase hex string of 64 characters.
+    pub fn hash_part_hex_upper(&self) -> String {
+        hex::encode_upper(self.hash_part)
+    }
+
+    /// Parses the `raw` address string (see [`Address::from_raw_address`]) and
+    /// checks whether it points to the same account.
+    ///
+    /// A string without a colon is not a raw address, so it's rejected
+    /// with a format error before any decoding happens.
+    pub fn equals_raw(&self, raw: &str) -> Result<bool, ParseError> {
+        Ok(Address::from_raw_address(raw)? == *self)
+    }
+
+    /// Checks whether both addresses share the same hash part but live in different
+    /// workchains, e.g. a masterchain and a basechain account with the same hash.
+    ///
+    /// Such addresses are different accounts and are never equal, this helper
+    /// only allows tooling to warn the user about the likely mix-up.
+    pub fn same_hash_different_workchain(&self, other: &Address) -> bool {
+        self.hash_part == other.hash_part && self.workchain != other.workchain
+    }
+
+    /// Attempt to create an [`Address`] structure from the
+    /// string representation of the raw address.
+    ///
+    /// ASCII whitespace surrounding the address is ignored.
+    ///
+    /// The address is parsed without intermediate allocations, the `address`
+    /// string of the [`ParseError`] is only allocated on the error path.
+    pub fn from_raw_address(str: &str) -> Result<Self, ParseError> {
+        let (workchain, hash) = match str.trim_ascii().split_once(':') {
+            Some(parts) => parts,
+            None => {
+                return Err(ParseError {
+                    address: str.to_owned(),
+                    reason: "Invalid raw address string: wrong address format",
+                    offset: None,
+                });
+            }
+        };
+
+        if hash.contains(':') {
+            return Err(ParseError {
+                address: str.to_owned(),
+                reason: "Invalid raw address string: more than one colon",
+                offset: None,
+            });
+        }
+
+        if workchain.is_empty() {
+            return Err(ParseError {
+                address: str.to_owned(),
+                reason: "Invalid raw address string: workchain number is empty",
+                offset: None,
+            });
+        }
+
+        if hash.is_empty() {
+            return Err(ParseError {
+                address: str.to_owned(),
+                reason: "Invalid raw address string: hash part is empty",
+                offset: None,
+            });
+        }
+
+        let wc = match workchain.parse::<i32>() {
+            Ok(wc) => wc,
+            Err(_) => {
+                return Err(ParseError {
+                    address: str.to_owned(),
                     reason: "Invalid raw address string: workchain number is not a 32-bit integer",
+                    offset: None,
+                });
+            }
+        };
+
+        if hash.len() % 2 != 0 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseError {
+                address: str.to_owned(),
+                reason: "Invalid raw address string: failed to decode hash part",
+                offset: None,
+            });
+        }
+
+        let mut hash_part: HashPart = [0u8; 32];
+
+        if hex::decode_to_slice(hash, &mut hash_part).is_err() {
+            return Err(ParseError {
+                address: str.to_owned(),
+                reason: "Invalid raw address string: hash part length must be 32 bytes",
+                offset: None,
+            });
+        }
+
+        Ok(Self {
+            workchain: wc,
+            hash_part,
+        })
+    }
+
+    /// Creates a new [`Address`] structure from a bare hash part of 64 hex digits
+    /// without the workchain prefix, using the supplied `workchain`.
+    ///
+    /// ASCII whitespace surrounding the hash is ignored. Use [`Address::from_raw_address`]
+    /// for the `workchain:hash_part` form.
+    pub fn from_hash_hex(hex: &str, workchain: Workchain) -> Result<Self, ParseError> {
+        let hash = hex.trim_ascii();
+
+        if hash.len() != 64 {
+            return Err(ParseError {
+                address: hex.to_owned(),
+                reason: "Invalid hash string: length must be 64 hex digits",
+                offset: None,
+            });
+        }
+
+        let mut hash_part: HashPart = [0u8; 32];
+
+        if hex::decode_to_slice(hash, &mut hash_part).is_err() {
+            return Err(ParseError {
+                address: hex.to_owned(),
+                reason: "Invalid hash string: failed to decode hex digits",
+                offset: None,
+            });
+        }
+
+        Ok(Self::new(workchain, &hash_part))
+    }
+
+    /// Same as [`Address::from_raw_address`], but only accepts the canonical form
+    /// produced by [`Address::to_raw_address`]: a workchain without a plus sign,
+    /// leading zeros or whitespace, and a lowercase hash part.
+    pub fn from_raw_address_strict(str: &str) -> Result<Self, ParseError> {
+        let address = Address::from_raw_address(str)?;
+
+        let (workchain, hash) = str
+            .split_once(':')
+            .expect("the address was validated by from_raw_address");
+
+        let error = |reason| ParseError {
+            address: str.to_owned(),
+            reason,
+            offset: None,
+        };
+
+        if workchain.starts_with(|c: char| c.is_ascii_whitespace()) {
+            return Err(error(
+                "Invalid raw address string: workchain number has leading whitespace",
+            ));
+        }
+
+        if workchain.starts_with('+') {
+            return Err(error(
+                "Invalid raw address string: workchain number has a plus sign",
+            ));
+        }
+
+        if workchain != address.workchain.to_string() {
+            return Err(error(
+                "Invalid raw address string: workchain number is not canonical",
+            ));
+        }
+
+        if hash.bytes().any(|b| b.is_ascii_uppercase()) {
+            return Err(error(
+                "Invalid raw address string: hash part must be lowercase",
+            ));
+        }
+
+        if hash != address.hash_part_hex() {
+            return Err(error(
+                "Invalid raw address string: hash part is not canonical",
+            ));
+        }
+
+        Ok(address)
+    }
+
+    /// Decodes the base64 address of the Ton network into an [`Address`] structure.
+    ///
+    /// If the `encoder` argument is specified, the method decodes the address “strictly”
+    /// according to the specified algorithm.
+    /// Otherwise, the address algorithm will be guessed by the presence of base64 control
+    /// characters. If the specified algorithm clearly can't apply, e.g. the address
+    /// contains `-` but the standard alphabet is specified, an alphabet mismatch
+    /// error is returned.
+    ///
+    /// ASCII whitespace surrounding the address is ignored.
+    pub fn from_base64(
+        address: &str,
+        encoder: Option<Base64Decoder>,
+    ) -> Result<EncoderResult, ParseError> {
+        let address = address.trim_ascii();
+        let (bytes, encoder) = Address::decode_base64_to_buffer(address, encoder)?;
+
+        let (address, flags) = Address::decode_buffer(address, &bytes)?;
+
+        Ok(EncoderResult {
+            address,
+            non_bounceable: !flags.is_bounceable(),
+            non_production: flags.is_testnet(),
+            decoder: encoder,
+            extended: false,
+        })
+    }
+
+    /// Parses the Base64 address at the front of `s` (see [`Address::from_base64`])
+    /// and returns the rest of the string, e.g. for addresses embedded in a longer token.
+    ///
+    /// Exactly the first 48 characters are consumed, leading whitespace is not skipped.
+    pub fn from_base64_prefix(s: &str) -> Result<(EncoderResult, &str), ParseError> {
+        if s.len() < 48 {
+            return Err(ParseError {
+                address: s.to_owned(),
+                reason: "Invalid base64 address string: length must be 48 characters",
+                offset: None,
+            });
+        }
+
+        if !s.is_char_boundary(48) {
+            return Err(ParseError {
+                address: s.to_owned(),
+                reason: NON_ASCII_REASON,
+                offset: None,
+            });
+        }
+
+        let (address, rest) = s.split_at(48);
+
+        if address.starts_with(|c: char| c.is_ascii_whitespace()) {
+            return Err(ParseError {
+                address: s.to_owned(),
+                reason: "Invalid base64 address string: address starts with whitespace",
+                offset: None,
+            });
+        }
+
+        Ok((Address::from_base64(address, None)?, rest))
+    }
+
+    /// Checks that the trimmed `address` is a 48 characters ASCII string and decodes it
+    /// into the 36 bytes, guessing the alphabet when `encoder` is `None`.
+    ///
+    /// A forced built-in alphabet that clearly can't apply (e.g. `-` or `_` with
+    /// [`Base64Decoder::Standard`]) is reported as an alphabet mismatch.
+    ///
+    /// Neither the tag byte nor the checksum are validated.
+    fn decode_base64_to_buffer(
+        address: &str,
+        encoder: Option<Base64Decoder>,
+    ) -> Result<([u8; 36], Base64Decoder), ParseError> {
+        if !address.is_ascii() {
+            return Err(ParseError {
+                address: address.to_owned(),
+                reason: NON_ASCII_REASON,
+                offset: None,
+            });
+        }
+
+        if address.len() != 48 {
+            return Err(ParseError {
+                address: address.to_owned(),
+                reason: "Invalid base64 address string: length must be 48 characters",
+                offset: None,
+            });
+        }
+
+        let mismatch = match encoder {
+            Some(Base64Decoder::Standard) => address.contains(['-', '_']),
+            Some(Base64Decoder::UrlSafe) => address.contains(['+', '/']),
+            _ => false,
+        };
+
+        if mismatch {
+            return Err(ParseError {
+                address: address.to_owned(),
+                reason: "Invalid base64 address string: alphabet mismatch",
+                offset: None,
+            });
+        }
+
+        let encoder = encoder.unwrap_or_else(|| Base64Decoder::guess(address));
+
+        Ok((encoder.decode_to_buffer(address)?, encoder))
+    }
+
+    /// Decodes an address produced by the non-standard [`Base64Encoder::Extended`]
+    /// encoder, which carries the full 32-bit workchain.
+    ///
+    /// The alphabet is guessed when `encoder` is `None`, like in [`Address::from_base64`].
+    /// Standard 48-character addresses are rejected.
+    pub fn from_base64_extended(
+        address: &str,
+        encoder: Option<Base64Decoder>,
+    ) -> Result<EncoderResult, ParseError> {
+        let address = address.trim_ascii();
+
+        if !address.is_ascii() {
+            return Err(ParseError {
+                address: address.to_owned(),
+                reason: NON_ASCII_REASON,
+                offset: None,
+            });
+        }
+
+        if address.len() != 52 {
+            return Err(ParseError {
+                address: address.to_owned(),
+                reason: "Invalid extended base64 address string: length must be 52 characters",
+                offset: None,
+            });
+        }
+
+        let encoder = encoder.unwrap_or_else(|| Base64Decoder::guess(address));
+        let mut bytes = [0u8; 39];
+
+        match encoder.engine().decode_slice(address, &mut bytes) {
+            Ok(39) => {}
+            Ok(_) | Err(DecodeSliceError::OutputSliceTooSmall) => {
+                return Err(ParseError {
+                    address: address.to_owned(),
+                    reason:
+                        "Invalid extended base64 address string: length of decoded bytes must be 39",
+                    offset: None,
+                });
+            }
+            Err(DecodeSliceError::DecodeError(_)) => {
+                return Err(ParseError {
+                    address: address.to_owned(),
+                    reason: "Invalid base64 address string: base64 decode error",
+                    offset: None,
+                });
+            }
+        }
+
+        let flags = match AddressFlags::from_tag_byte(bytes[0].wrapping_sub(1)) {
+            Some(flags) => flags,
+            None => {
+                return Err(ParseError {
+                    address: address.to_owned(),
+                    reason: "Invalid extended base64 address string: invalid flag",
+                    offset: Some(0),
+                });
+            }
+        };
+
+        let server_crc = crc16(&bytes[0..37]);
+        let client_crc = ((bytes[37] as u16) << 8) | (bytes[38] as u16);
+
+        if server_crc != client_crc {
+            return Err(ParseError {
+                address: address.to_owned(),
+                reason: "Invalid base64 address string: CRC16 hashes do not match",
+                offset: Some(37),
+            });
+        }
+
+        let mut workchain = [0u8; 4];
+        workchain.clone_from_slice(&bytes[1..5]);
+
+        let mut hash_part: HashPart = [0u8; 32];
+        hash_part.clone_from_slice(&bytes[5..37]);
+
+        Ok(EncoderResult {
+            address: Address {
+                workchain: Workchain::from_be_bytes(workchain),
+                hash_part,
+            },
+            non_bounceable: !flags.is_bounceable(),
+            non_production: flags.is_testnet(),
+            decoder: encoder,
+            extended: true,
+        })
+    }
+
+    /// Same as [`Address::from_base64`], but tolerant to the way the address was produced:
+    /// all ASCII whitespace (including whitespace inside the address) and trailing `=`
+    /// padding are removed before the address is validated.
+    ///
+    /// Use [`Address::from_base64`] for the exact validation.
+    pub fn from_base64_lenient(
+        address: &str,
+        encoder: Option<Base64Decoder>,
+    ) -> Result<EncoderResult, ParseError> {
+        let normalized = address
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect::<String>();
+
+        Address::from_base64(normalized.trim_end_matches('='), encoder).map_err(|err| ParseError {
+            address: address.to_owned(),
+            ..err
+        })
+    }
+
+    /// Validates the length, the tag byte and the CRC16 checksum of the decoded
+    /// address and extracts its workchain, hash part and flags.
+    ///
+    /// This is the single place where the 36 bytes are taken apart, shared by the
+    /// Base64, codec and byte slice entry points. The length is checked before any
+    /// indexing, so short buffers produce an error instead of a panic.
+    ///
+    /// The `address` argument is only used for error reporting.
+    fn decode_buffer(address: &str, bytes: &[u8]) -> Result<(Address, AddressFlags), ParseError> {
+        if bytes.len() != 36 {
+            return Err(ParseError {
+                address: address.to_owned(),
+                reason: "Invalid base64 address string: length of decoded bytes must be 36",
+                offset: None,
+            });
+        }
+
+        let flags = match AddressFlags::from_tag_byte(bytes[0]) {
+            Some(flags) => flags,
+            None => {
+                return Err(ParseError {
+                    address: address.to_owned(),
+                    reason: "Invalid base64 address string: invalid flag",
+                    offset: Some(0),
+                });
+            }
+        };
+
+        // The workchain is stored as a signed byte, e.g. 0xFF is the masterchain (-1).
+        let workchain = bytes[1] as i8 as i32;
+
+        let server_crc = crc16(&bytes[0..34]);
+        let client_crc = ((bytes[34] as u16) << 8) | (bytes[35] as u16);
+
+        if server_crc != client_crc {
+            return Err(ParseError {
+                address: address.to_owned(),
+                reason: "Invalid base64 address string: CRC16 hashes do not match",
+                offset: Some(34),
+            });
+        }
+
+        let mut hash_part: HashPart = [0u8; 32];
+        hash_part.clone_from_slice(&bytes[2..34]);
+
+        Ok((
+            Address {
+                workchain,
+                hash_part,
+            },
+            flags,
+        ))
+    }
+
+    /// Same as [`Address::from_base64`], but the address is decoded with
+    /// a custom [`AddressCodec`].
+    ///
+    /// Since a custom codec has no matching [`Base64Decoder`], the flags are
+    /// returned separately instead of an [`EncoderResult`].
+    pub fn from_base64_with_codec<C: AddressCodec + ?Sized>(
+        address: &str,
+        codec: &C,
+    ) -> Result<(Address, AddressFlags), ParseError> {
+        let bytes = codec.decode(address)?;
+
+        Address::decode_buffer(address, &bytes)
+    }
+
+    /// Same as [`Address::from_base64`], but accepts the Base64 address as a byte slice,
+    /// e.g. read directly from a buffer.
+    ///
+    /// The bytes must be ASCII, otherwise an error is returned.
+    pub fn from_base64_bytes(
+        bytes: &[u8],
+        encoder: Option<Base64Decoder>,
+    ) -> Result<EncoderResult, ParseError> {
+        if !bytes.is_ascii() {
+            return Err(ParseError {
+                address: String::from_utf8_lossy(bytes).into_owned(),
+                reason: NON_ASCII_REASON,
+                offset: None,
+            });
+        }
+
+        let address = std::str::from_utf8(bytes).expect("ASCII bytes are always valid UTF-8");
+
+        Address::from_base64(address, encoder)
+    }
+
+    /// Parses an address in any of the forms accepted by [`FromStr`] and
+    /// reports the format it was interpreted as.
+    ///
+    /// For Base64 addresses the format reflects the alphabet used to decode
+    /// the address, see [`detect_format`].
+    pub fn parse(s: &str) -> Result<(Address, AddressFormat), ParseError> {
+        let s = s.trim_ascii();
+
+        if s.contains(':') {
+            return Ok((Address::from_raw_address(s)?, AddressFormat::Raw));
+        }
+
+        let result = Address::from_base64(s, None)?;
+        let format = match result.decoder {
+            Base64Decoder::UrlSafe => AddressFormat::Base64UrlSafe,
+            _ => AddressFormat::Base64Standard,
+        };
+
+        Ok((result.address, format))
+    }
+
+    /// Parses an address in any of the forms accepted by [`FromStr`] and checks
+    /// that its workchain is one of the `allowed` workchains.
+    pub fn from_str_in_workchains(s: &str, allowed: &[Workchain]) -> Result<Self, ParseError> {
+        let address = s.parse::<Address>()?;
+
+        if !allowed.contains(&address.workchain) {
+            return Err(ParseError {
+                address: s.to_owned(),
+                reason: "Invalid address: workchain is not allowed",
+                offset: None,
+            });
+        }
+
+        Ok(address)
+    }
+
+    /// Parses newline delimited addresses from the `reader` line by line,
+    /// without loading the whole input into memory.
+    ///
+    /// Lines are trimmed and blank lines are skipped. Every other line yields
+    /// the parse result of [`FromStr`].
+    ///
+    /// A line that isn't valid UTF-8 yields an error with a dedicated reason and
+    /// its lossy decoded content, and the following lines are still parsed.
+    /// A failed read yields an error with another dedicated reason and ends
+    /// the iterator. Both errors store the 1-based line number in `offset`.
+    pub fn parse_reader<R: Read>(reader: R) -> impl Iterator<Item = Result<Address, ParseError>> {
+        let mut failed = false;
+
+        BufReader::new(reader)
+            .split(b'\n')
+            .enumerate()
+            .map_while(move |(index, line)| {
+                if failed {
+                    return None;
+                }
+
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => {
+                        failed = true;
+
+                        return Some(Some(Err(ParseError {
+                            address: String::new(),
+                            reason: "Invalid address stream: failed to read line",
+                            offset: Some(index + 1),
+                        })));
+                    }
+                };
+
+                let line = line.trim_ascii();
+
+                if line.is_empty() {
+                    return Some(None);
+                }
+
+                Some(Some(match std::str::from_utf8(line) {
+                    Ok(line) => line.parse::<Address>(),
+                    Err(_) => Err(ParseError {
+                        address: String::from_utf8_lossy(line).into_owned(),
+                        reason: "Invalid address stream: line is not valid UTF-8",
+                        offset: Some(index + 1),
+                    }),
+                }))
+            })
+            .flatten()
+    }
+
+    /// Converts the current structure to a string of the form “0:fa16bc...”
+    /// also known as the “raw address”.
+    pub fn to_raw_address(&self) -> String {
+        format!("{}:{}", self.workchain, hex::encode(self.hash_part))
+    }
+
+    /// Same as [`Address::to_raw_address`], but the hash part is encoded
+    /// in uppercase hex, e.g. “0:FA16BC...”.
+    pub fn to_raw_address_upper(&self) -> String {
+        format!("{}:{}", self.workchain, hex::encode_upper(self.hash_part))
+    }
+
+    /// Converts the current structure to a Base64 string according to
+    /// the specified preferences in the `encoder` argument.
+    ///
+    /// Use the [`BASE64_STD_DEFAULT`] and [`BASE64_URL_DEFAULT`] constants for fast conversion.
+    ///
+    /// The standard forms store the workchain in a single byte, so a workchain
+    /// outside of the `i8` range is truncated. The fallible helpers such as
+    /// [`Address::write_base64`] and [`Address::to_raw_bytes`] reject it instead.
+    pub fn to_base64(&self, encoder: Base64Encoder) -> String {
+        encoder.encode(self.workchain, &self.hash_part)
+    }
+
+    /// Returns the encoder for "the normal way to display this address":
+    /// bounceable and mainnet, with the url safe alphabet if `url_safe` is set,
+    /// i.e. [`BASE64_URL_DEFAULT`] or [`BASE64_STD_DEFAULT`].
+    ///
+    /// Currently the same for every workchain. Callers wanting a different
+    /// convention (e.g. non-bounceable for wallets) should pick their own encoder.
+    pub fn default_encoder(&self, url_safe: bool) -> Base64Encoder {
+        if url_safe {
+            BASE64_URL_DEFAULT
+        } else {
+            BASE64_STD_DEFAULT
+        }
+    }
+
+    /// Returns the non-bounceable testnet form of the address (`0Q...` for the basechain),
+    /// the form expected by faucets and testnet wallets.
+    ///
+    /// A shortcut for [`BASE64_URL_TESTNET_NON_BOUNCEABLE`] and
+    /// [`BASE64_STD_TESTNET_NON_BOUNCEABLE`].
+    pub fn to_testnet_non_bounceable(&self, url_safe: bool) -> String {
+        if url_safe {
+            self.to_base64(BASE64_URL_TESTNET_NON_BOUNCEABLE)
+        } else {
+            self.to_base64(BASE64_STD_TESTNET_NON_BOUNCEABLE)
+        }
+    }
+
+    /// Same as [`Address::to_base64`], but returns an [`AddressWarning::NullAddress`]
+    /// carrying the encoded string if the address [is zero](Address::is_zero),
+    /// which is valid but often a bug.
+    pub fn to_base64_checked(&self, encoder: Base64Encoder) -> Result<String, AddressWarning> {
+        let encoded = self.to_base64(encoder);
+
+        if self.is_zero() {
+            return Err(AddressWarning::NullAddress { encoded });
+        }
+
+        Ok(encoded)
+    }
+
+    /// Same as [`Address::to_base64`], but takes reusable [`EncodeOptions`].
+    pub fn to_base64_with_options(&self, options: &EncodeOptions) -> String {
+        self.to_base64(options.to_encoder())
+    }
+
+    /// Returns a truncated form of the Base64 representation for display,
+    /// e.g. `EQAO…JuR2` for `head = 4` and `tail = 4`.
+    ///
+    /// If `head + tail` doesn't leave at least one character to hide, the full
+    /// string is returned without an ellipsis, so the result is never ambiguous.
+    pub fn to_short_display(&self, encoder: Base64Encoder, head: usize, tail: usize) -> String {
+        let encoded = self.to_base64(encoder);
+
+        if head.saturating_add(tail) >= encoded.len() {
+            return encoded;
+        }
+
+        format!("{}…{}", &encoded[..head], &encoded[encoded.len() - tail..])
+    }
+
+    /// Same as [`Address::to_base64`], but returns the ASCII bytes of the encoded
+    /// address, e.g. to feed them directly into a writer.
+    pub fn to_base64_bytes(&self, encoder: Base64Encoder) -> Vec<u8> {
+        encoder.encode_with(self.workchain, &self.hash_part, <[u8]>::to_vec)
+    }
+
+    /// Same as [`Address::to_base64`], but writes the 48 characters into the `out`
+    /// buffer instead of allocating a [`String`].
+    ///
+    /// Returns an error for the [`Base64Encoder::Extended`] encoder, since
+    /// its 52 characters don't fit in the buffer, and for a workchain that
+    /// doesn't fit in a single byte, see [`Address::to_raw_bytes`].
+    pub fn write_base64(
+        &self,
+        encoder: Base64Encoder,
+        out: &mut [u8; 48],
+    ) -> Result<(), ParseError> {
+        if let Base64Encoder::Extended { .. } = encoder {
+            return Err(ParseError {
+                address: self.to_raw_address(),
+                reason: "Invalid encoder: extended addresses don't fit in 48 bytes",
+                offset: None,
+            });
+        }
+
+        let buffer = self.to_raw_bytes(encoder)?;
+
+        encoder
+            .alphabet()
+            .engine()
+            .encode_slice(buffer, out)
+            .expect("36 bytes are always encoded to 48 characters without padding");
+
+        Ok(())
+    }
+
+    /// Converts the current structure to a string with a custom [`AddressCodec`],
+    /// storing the given `flags` in the tag byte.
+    ///
+    /// Codecs work with the 36-byte layout, so an error is returned if the workchain
+    /// doesn't fit in a single byte, see [`Address::to_raw_bytes`].
+    pub fn to_base64_with_codec<C: AddressCodec + ?Sized>(
+        &self,
+        flags: AddressFlags,
+        codec: &C,
+    ) -> Result<String, ParseError> {
+        Ok(codec.encode(&self.to_raw_bytes(Base64Encoder::standard(flags))?))
+    }
+
+    /// Checks whether the Base64 representation of the address starts with `prefix`,
+    /// e.g. when searching for a vanity address.
+    ///
+    /// The address is encoded into a stack buffer, so no allocation happens.
+    pub fn has_friendly_prefix(&self, prefix: &str, encoder: Base64Encoder) -> bool {
+        encoder.encode_with(self.workchain, &self.hash_part, |encoded| {
+            encoded.starts_with(prefix.as_bytes())
+        })
+    }
+
+    /// Same as [`Address::has_friendly_prefix`], but ASCII characters are
+    /// compared case-insensitively.
+    pub fn has_friendly_prefix_ignore_case(&self, prefix: &str, encoder: Base64Encoder) -> bool {
+        encoder.encode_with(self.workchain, &self.hash_part, |encoded| {
+            prefix.len() <= encoded.len()
+                && encoded[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+        })
+    }
+
+    /// Returns the number of leading Base64 characters shared by both addresses
+    /// when encoded with the same `encoder`.
+    ///
+    /// Useful to decide how many characters a truncated display must show to
+    /// tell the addresses apart. Both addresses are encoded into stack buffers.
+    pub fn friendly_common_prefix_len(&self, other: &Address, encoder: Base64Encoder) -> usize {
+        encoder.encode_with(self.workchain, &self.hash_part, |a| {
+            encoder.encode_with(other.workchain, &other.hash_part, |b| {
+                a.iter().zip(b).take_while(|(a, b)| a == b).count()
+            })
+        })
+    }
+
+    /// Returns the 36 bytes hidden behind the Base64 representation of the address:
+    /// the tag byte, the workchain byte, the hash part and the CRC16 checksum.
+    ///
+    /// The alphabet of the `encoder` argument does not affect the result.
+    ///
+    /// Returns an error for the [`Base64Encoder::Extended`] encoder, which doesn't
+    /// use the 36-byte layout, and for a workchain that doesn't fit in a single byte.
+    /// The same applies to [`Address::checksum`] and [`Address::payload_bytes`].
+    pub fn to_raw_bytes(&self, encoder: Base64Encoder) -> Result<[u8; 36], ParseError> {
+        if let Base64Encoder::Extended { .. } = encoder {
+            return Err(ParseError {
+                address: self.to_raw_address(),
+                reason: "Invalid encoder: extended addresses don't have the 36-byte layout",
+                offset: None,
+            });
+        }
+
+        if WorkchainId::new(self.workchain).is_none() {
+            return Err(ParseError {
+                address: self.to_raw_address(),
+                reason: "Invalid workchain: the workchain doesn't fit in a single byte",
+                offset: None,
+            });
+        }
+
+        Ok(encoder.build_buffer(self.workchain, &self.hash_part))
+    }
+
+    /// Returns the CRC16 checksum of the address encoded with the flags of
+    /// the `encoder` argument.
+    pub fn checksum(&self, encoder: Base64Encoder) -> Result<u16, ParseError> {
+        Ok(crc16(&self.payload_bytes(encoder)?))
+    }
+
+    /// Returns the 34 bytes the CRC16 checksum is computed over: the tag byte,
+    /// the workchain byte and the hash part, i.e. [`Address::to_raw_bytes`]
+    /// without the checksum. Also known as the tagged payload, see
+    /// [`Address::from_tagged_payload`] for the reverse conversion.
+    ///
+    /// The alphabet of the `encoder` argument does not affect the result.
+    #[doc(alias = "to_tagged_payload")]
+    pub fn payload_bytes(&self, encoder: Base64Encoder) -> Result<[u8; 34], ParseError> {
+        let mut payload = [0u8; 34];
+        payload.clone_from_slice(&self.to_raw_bytes(encoder)?[0..34]);
+
+        Ok(payload)
+    }
+
+    /// Restores the address and its flags from a tagged payload produced by
+    /// [`Address::payload_bytes`]. The CRC16 checksum is recomputed,
+    /// so only the tag byte is validated.
+    pub fn from_tagged_payload(payload: &[u8; 34]) -> Result<(Address, AddressFlags), ParseError> {
+        let mut bytes = [0u8; 36];
+        bytes[0..34].clone_from_slice(payload);
+        bytes[34..36].clone_from_slice(&crc16(payload).to_be_bytes());
+
+        Address::decode_buffer(&hex::encode(payload), &bytes)
+    }
+
+    /// Returns the canonical string representation of the address: the url safe,
+    /// bounceable, mainnet Base64 form (same as [`Display`]).
+    ///
+    /// Note that the friendly flags and the alphabet of the original string are
+    /// deliberately discarded, so that every form of the same account produces
+    /// the same canonical string.
+    pub fn canonical(&self) -> String {
+        self.to_base64(BASE64_URL_DEFAULT)
+    }
+
+    /// Parses an address in any of the accepted forms and returns
+    /// its canonical string representation.
+    ///
+    /// See [`Address::canonical`] for details.
+    pub fn normalize(s: &str) -> Result<String, ParseError> {
+        Ok(s.parse::<Address>()?.canonical())
+    }
+
+    /// Yields every string the address may appear as: the four flag combinations
+    /// in the url safe and then in the standard alphabet, followed by the raw form.
+    ///
+    /// Useful to index the account under each alias. Note that an address without
+    /// `+`, `/`, `-` and `_` characters looks the same in both alphabets, so some
+    /// of the nine strings may be duplicates.
+    pub fn friendly_aliases(&self) -> impl Iterator<Item = String> + '_ {
+        [Base64Decoder::UrlSafe, Base64Decoder::Standard]
+            .into_iter()
+            .flat_map(move |alphabet| {
+                [
+                    (Network::Mainnet, Bounce::Bounceable),
+                    (Network::Mainnet, Bounce::NonBounceable),
+                    (Network::Testnet, Bounce::Bounceable),
+                    (Network::Testnet, Bounce::NonBounceable),
+                ]
+                .into_iter()
+                .map(move |(network, bounce)| {
+                    self.to_base64(Base64Encoder::new(alphabet, network, bounce))
+                })
+            })
+            .chain(std::iter::once_with(move || self.to_raw_address()))
+    }
+
+    /// Returns the raw form and all four url safe Base64 forms of the address,
+    /// see [`AddressRepresentations`].
+    pub fn to_base64_all(&self) -> AddressRepresentations {
+        let encode = |network, bounce| {
+            self.to_base64(Base64Encoder::new(Base64Decoder::UrlSafe, network, bounce))
+        };
+
+        AddressRepresentations {
+            raw: self.to_raw_address(),
+            bounceable: encode(Network::Mainnet, Bounce::Bounceable),
+            non_bounceable: encode(Network::Mainnet, Bounce::NonBounceable),
+            testnet_bounceable: encode(Network::Testnet, Bounce::Bounceable),
+            testnet_non_bounceable: encode(Network::Testnet, Bounce::NonBounceable),
+        }
+    }
+}
+
+/// The raw form and all four url safe Base64 forms of the same address,
+/// see [`Address::to_base64_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressRepresentations {
+    /// `workchain:hash_part` in lowercase hex.
+    pub raw: String,
+    /// Bounceable, mainnet (`EQ...` for the basechain).
+    pub bounceable: String,
+    /// Non-bounceable, mainnet (`UQ...` for the basechain).
+    pub non_bounceable: String,
+    /// Bounceable, testnet (`kQ...` for the basechain).
+    pub testnet_bounceable: String,
+    /// Non-bounceable, testnet (`0Q...` for the basechain).
+    pub testnet_non_bounceable: String,
+}
+
+impl Display for AddressRepresentations {
+    /// Writes one representation per line, e.g. for debug output:
+    ///
+    /// ```text
+    /// raw:                      0:0e97...a026
+    /// bounceable:               EQAO...JuR2
+    /// non-bounceable:           UQAO...Jrmz
+    /// testnet bounceable:       kQAO...Jl_8
+    /// testnet non-bounceable:   0QAO...JgI5
+    /// ```
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "raw:                      {}", self.raw)?;
+        writeln!(f, "bounceable:               {}", self.bounceable)?;
+        writeln!(f, "non-bounceable:           {}", self.non_bounceable)?;
+        writeln!(f, "testnet bounceable:       {}", self.testnet_bounceable)?;
+        write!(
+            f,
+            "testnet non-bounceable:   {}",
+            self.testnet_non_bounceable
+        )
+    }
+}
+
+impl FromStr for Address {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_ascii();
+
+        if s.contains(':') {
+            Address::from_raw_address(s)
+        } else {
+            Ok(Address::from_base64(s, None)?.address)
+        }
+    }
+}
+
+impl TryFrom<String> for Address {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse::<Address>()
+    }
+}
+
+impl Display for Address {
+    /// Writes the url safe, bounceable, mainnet Base64 form of the address.
+    ///
+    /// With the alternate flag (`{:#}`) the raw address is written instead.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return f.write_str(self.to_raw_address().as_str());
+        }
+
+        f.write_str(self.to_base64(BASE64_URL_DEFAULT).as_str())
+    }
+}
+
+impl From<HashPart> for Address {
+    /// Creates an [`Address`] in the basechain (workchain `0`).
+    fn from(value: HashPart) -> Self {
+        Address::new(0, &value)
+    }
+}
+
+impl From<(Workchain, HashPart)> for Address {
+    fn from((workchain, hash_part): (Workchain, HashPart)) -> Self {
+        Address::new(workchain, &hash_part)
+    }
+}
+
+impl TryFrom<&[u8]> for Address {
+    type Error = ParseError;
+
+    /// Decodes the 36 bytes hidden behind the Base64 representation of an address
+    /// (see [`Address::to_raw_bytes`]), validating the tag byte and the CRC16 checksum.
+    ///
+    /// The `address` of the error is the hex representation of the bytes.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Address::decode_buffer(&hex::encode(bytes), bytes)?.0)
+    }
+}
+
+impl TryFrom<Vec<u8>> for Address {
+    type Error = ParseError;
+
+    /// Same as `TryFrom<&[u8]>`.
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        Address::try_from(bytes.as_slice())
+    }
+}
+
+impl AsRef<[u8]> for Address {
+    /// Returns the hash part of the address. Note that the workchain is not included.
+    fn as_ref(&self) -> &[u8] {
+        &self.hash_part
+    }
+}
+
+impl AsRef<HashPart> for Address {
+    /// Returns the hash part of the address. Note that the workchain is not included.
+    fn as_ref(&self) -> &HashPart {
+        &self.hash_part
+    }
+}
+
+impl From<Address> for String {
+    fn from(value: Address) -> Self {
+        value.to_base64(BASE64_URL_DEFAULT)
+    }
+}
+
+impl From<&Address> for String {
+    fn from(value: &Address) -> Self {
+        value.to_base64(BASE64_URL_DEFAULT)
+    }
+}
+
+/// An [`Address`] wrapper meant to be used as a map key: it is parsed from any form
+/// accepted by [`FromStr`] and always displayed in the canonical form
+/// (see [`Address::canonical`]), so every form of the same account is the same key.
+///
+/// Unlike [`Address`], it is ordered by workchain and then by hash part.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CanonicalAddress(pub Address);
+
+impl PartialOrd for CanonicalAddress {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CanonicalAddress {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.0.workchain, self.0.hash_part).cmp(&(other.0.workchain, other.0.hash_part))
+    }
+}
+
+impl FromStr for CanonicalAddress {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(CanonicalAddress(s.parse::<Address>()?))
+    }
+}
+
+impl Display for CanonicalAddress {
+    /// Writes the canonical url safe, bounceable, mainnet Base64 form.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.canonical())
+    }
+}
+
+impl From<Address> for CanonicalAddress {
+    fn from(value: Address) -> Self {
+        CanonicalAddress(value)
+    }
+}
+
+#[cfg(feature = "rusqlite")]
+impl rusqlite::ToSql for Address {
+    /// Stores the address as the url safe, bounceable, mainnet Base64 string.
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(rusqlite::types::ToSqlOutput::from(
+            self.to_base64(BASE64_URL_DEFAULT),
+        ))
+    }
+}
+
+#[cfg(feature = "rusqlite")]
+impl rusqlite::types::FromSql for Address {
+    /// Reads the address from a text value in any of the forms accepted by [`FromStr`].
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse::<Address>()
+            .map_err(|err| rusqlite::types::FromSqlError::Other(Box::new(err)))
+    }
+}
+
+#[cfg(feature = "diesel")]
+impl diesel::serialize::ToSql<diesel::sql_types::Text, diesel::pg::Pg> for Address {
+    /// Stores the address as the url safe, bounceable, mainnet Base64 string.
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, diesel::pg::Pg>,
+    ) -> diesel::serialize::Result {
+        use std::io::Write;
+
+        out.write_all(self.to_base64(BASE64_URL_DEFAULT).as_bytes())?;
+
+        Ok(diesel::serialize::IsNull::No)
+    }
+}
+
+#[cfg(feature = "diesel")]
+impl diesel::deserialize::FromSql<diesel::sql_types::Text, diesel::pg::Pg> for Address {
+    /// Reads the address from a text value in any of the forms accepted by [`FromStr`].
+    fn from_sql(bytes: diesel::pg::PgValue<'_>) -> diesel::deserialize::Result<Self> {
+        let value = std::str::from_utf8(bytes.as_bytes())?;
+
+        Ok(value.parse::<Address>()?)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for EncoderResult {
+    /// Serializes the result to the Base64 string it was decoded from,
+    /// preserving the flags and the alphabet.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_base64())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for EncoderResult {
+    /// Deserializes the result from a Base64 string, guessing its alphabet.
+    ///
+    /// Extended addresses (52 characters, see [`Base64Encoder::Extended`]) are
+    /// decoded with [`Address::from_base64_extended`], so serialized extended
+    /// results keep their full workchain.
+    ///
+    /// Note that strings encoded with a custom alphabet can't be deserialized.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let address = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+
+        if address.trim_ascii().len() == 52 {
+            return Address::from_base64_extended(&address, None).map_err(serde::de::Error::custom);
+        }
+
+        Address::from_base64(&address, None).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serializes an [`Address`] as the raw `workchain:hash_part` string,
+/// use it with `#[serde(with = "ton_address::serde_raw")]`.
+#[cfg(feature = "serde")]
+pub mod serde_raw {
+    use super::Address;
+
+    pub fn serialize<S: serde::Serializer>(
+        address: &Address,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&address.to_raw_address())
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Address, D::Error> {
+        let address =
+            <std::borrow::Cow<'de, str> as serde::Deserialize>::deserialize(deserializer)?;
+
+        Address::from_raw_address(&address).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serializes an [`Address`] as the url safe, bounceable, mainnet Base64 string,
+/// use it with `#[serde(with = "ton_address::serde_friendly_url")]`.
+///
+/// Any url safe Base64 address is accepted on deserialization, its flags are discarded.
+#[cfg(feature = "serde")]
+pub mod serde_friendly_url {
+    use super::{Address, Base64Decoder, EncoderResult, BASE64_URL_DEFAULT};
+
+    pub fn serialize<S: serde::Serializer>(
+        address: &Address,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&address.to_base64(BASE64_URL_DEFAULT))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Address, D::Error> {
+        let address =
+            <std::borrow::Cow<'de, str> as serde::Deserialize>::deserialize(deserializer)?;
+
+        Address::from_base64(&address, Some(Base64Decoder::UrlSafe))
+            .map(EncoderResult::into_address)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serializes an [`Address`] as the standard, bounceable, mainnet Base64 string,
+/// use it with `#[serde(with = "ton_address::serde_friendly_std")]`.
+///
+/// Any standard Base64 address is accepted on deserialization, its flags are discarded.
+#[cfg(feature = "serde")]
+pub mod serde_friendly_std {
+    use super::{Address, Base64Decoder, EncoderResult, BASE64_STD_DEFAULT};
+
+    pub fn serialize<S: serde::Serializer>(
+        address: &Address,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&address.to_base64(BASE64_STD_DEFAULT))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Address, D::Error> {
+        let address =
+            <std::borrow::Cow<'de, str> as serde::Deserialize>::deserialize(deserializer)?;
+
+        Address::from_base64(&address, Some(Base64Decoder::Standard))
+            .map(EncoderResult::into_address)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Detects the format of the `address` argument using the same heuristics
+/// as [`FromStr`]: a colon means a raw address, otherwise the Base64 alphabet
+/// is guessed by the presence of its control characters.
+///
+/// The address is not validated, an address without control characters is
+/// reported as [`AddressFormat::Base64Standard`] since it's compatible with
+/// both alphabets.
+pub fn detect_format(address: &str) -> AddressFormat {
+    let address = address.trim_ascii();
+
+    if address.contains(':') {
+        return AddressFormat::Raw;
+    }
+
+    if address.len() != 48 {
+        return AddressFormat::Unknown;
+    }
+
+    match Base64Decoder::guess(address) {
+        Base64Decoder::UrlSafe => AddressFormat::Base64UrlSafe,
+        _ => AddressFormat::Base64Standard,
+    }
+}
+
+/// Checks whether the `address` argument is a valid Base64 address,
+/// guessing its alphabet.
+///
+/// The same checks as in [`Address::from_base64`] are performed
+/// (length, flag and CRC16), but the decoded address is discarded.
+pub fn is_valid_base64(address: &str) -> bool {
+    Address::from_base64(address, None).is_ok()
+}
+
+/// Checks whether the `address` argument is a valid address in any
+/// of the supported forms, raw or Base64.
+pub fn is_valid(address: &str) -> bool {
+    address.parse::<Address>().is_ok()
+}
+
+/// Parses every address string and pairs its index with the parse result,
+/// so that failed rows can be reported.
+pub fn validate_many<I: IntoIterator<Item = S>, S: AsRef<str>>(
+    iter: I,
+) -> Vec<(usize, Result<Address, ParseError>)> {
+    iter.into_iter()
+        .enumerate()
+        .map(|(index, s)| (index, s.as_ref().parse::<Address>()))
+        .collect()
+}
+
+/// Parses every address string and partitions the results into the valid
+/// addresses and the errors, both in the input order.
+///
+/// Unlike [`validate_many`], the results are not interleaved, which is handy
+/// for best-effort imports.
+pub fn parse_lossy<I: IntoIterator<Item = S>, S: AsRef<str>>(
+    iter: I,
+) -> (Vec<Address>, Vec<ParseError>) {
+    let mut addresses = Vec::new();
+    let mut errors = Vec::new();
+
+    for s in iter {
+        match s.as_ref().parse::<Address>() {
+            Ok(address) => addresses.push(address),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    (addresses, errors)
+}
+
+/// Converts a raw address to its Base64 representation according to
+/// the preferences in the `encoder` argument.
+pub fn raw_to_friendly(raw: &str, encoder: Base64Encoder) -> Result<String, ParseError> {
+    Ok(Address::from_raw_address(raw)?.to_base64(encoder))
+}
+
+/// Converts account hashes sharing the same `workchain` (e.g. a column of
+/// basechain account ids) to their Base64 representations.
+pub fn hashes_to_friendly<I: IntoIterator<Item = HashPart>>(
+    hashes: I,
+    workchain: Workchain,
+    encoder: Base64Encoder,
+) -> Vec<String> {
+    hashes
+        .into_iter()
+        .map(|hash_part| encoder.encode(workchain, &hash_part))
+        .collect()
+}
+
+/// Re-encodes a Base64 address using the url safe alphabet (`-` and `_`),
+/// preserving its bounceable and testnet flags.
+pub fn to_url_safe(address: &str) -> Result<String, ParseError> {
+    let result = Address::from_base64(address, None)?;
+
+    Ok(result
+        .address
+        .to_base64(Base64Encoder::url_safe(result.flags())))
+}
+
+/// Re-encodes a Base64 address using the standard alphabet (`+` and `/`),
+/// preserving its bounceable and testnet flags.
+pub fn to_standard(address: &str) -> Result<String, ParseError> {
+    let result = Address::from_base64(address, None)?;
+
+    Ok(result
+        .address
+        .to_base64(Base64Encoder::standard(result.flags())))
+}
+
+/// Recomputes the CRC16 checksum of a Base64 address and re-encodes it with the
+/// same alphabet, so that the result passes the checksum validation.
+///
+/// **This trusts the payload bytes**: the tag byte, the workchain and the hash part
+/// are kept as is and only the checksum is replaced. If the corruption is in the
+/// payload, the result is a valid string for a *different* account, so only use this
+/// when the checksum itself is known to be wrong. An invalid tag byte is not fixed either.
+pub fn repair_crc(address: &str) -> Result<String, ParseError> {
+    let address = address.trim_ascii();
+    let (mut bytes, encoder) = Address::decode_base64_to_buffer(address, None)?;
+
+    let crc = crc16(&bytes[0..34]);
+    bytes[34..36].clone_from_slice(&crc.to_be_bytes());
+
+    Ok(encoder.engine().encode(bytes))
+}
+
+/// Parses a `ton://transfer/<address>?amount=...&text=...` deep link and returns
+/// the address together with the percent-decoded query parameters
+/// (`+` is decoded as a space).
+///
+/// The address may be in any of the forms accepted by [`FromStr`].
+/// URIs with other schemes or actions are rejected.
+pub fn from_ton_uri(uri: &str) -> Result<(Address, BTreeMap<String, String>), ParseError> {
+    let error = |reason| ParseError {
+        address: uri.to_owned(),
+        reason,
+        offset: None,
+    };
+
+    let uri = uri.trim_ascii();
+
+    let rest = match uri.get(..6) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("ton://") => &uri[6..],
+        _ => return Err(error("Invalid ton URI: scheme must be ton://")),
+    };
+
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+    let address = match path.split_once('/') {
+        Some(("transfer", address)) => address,
+        _ => return Err(error("Invalid ton URI: expected ton://transfer/<address>")),
+    };
+
+    let address = address.parse::<Address>().map_err(|err| ParseError {
+        address: uri.to_owned(),
+        ..err
+    })?;
+
+    let mut params = BTreeMap::new();
+
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+        let key = percent_decode(key)
+            .ok_or_else(|| error("Invalid ton URI: malformed percent-encoding"))?;
+        let value = percent_decode(value)
+            .ok_or_else(|| error("Invalid ton URI: malformed percent-encoding"))?;
+
+        params.insert(key, value);
+    }
+
+    Ok((address, params))
+}
+
+/// Parses an address decoded from a QR code, where scanners may add a `ton://`
+/// scheme (in any case) and a `transfer/` segment around the address.
+///
+/// The address itself is parsed as is (see [`FromStr`]), the Base64 form is
+/// case-sensitive. An uppercased Base64 payload can't be restored,
+/// so it's rejected with a dedicated error.
+pub fn from_scanned(s: &str) -> Result<Address, ParseError> {
+    let mut body = s.trim_ascii();
+
+    if let Some(scheme) = body.get(..6) {
+        if scheme.eq_ignore_ascii_case("ton://") {
+            body = &body[6..];
+        }
+    }
+
+    if let Some(action) = body.get(..9) {
+        if action.eq_ignore_ascii_case("transfer/") {
+            body = &body[9..];
+        }
+    }
+
+    let body = body.split_once('?').map_or(body, |(body, _)| body);
+
+    body.parse::<Address>().map_err(|err| {
+        let uppercased = !body.contains(':')
+            && body.bytes().any(|b| b.is_ascii_uppercase())
+            && !body.bytes().any(|b| b.is_ascii_lowercase());
+
+        if uppercased {
+            ParseError {
+                address: s.to_owned(),
+                reason: "Invalid scanned address: the base64 payload was uppercased",
+                offset: None,
+            }
+        } else {
+            ParseError {
+                address: s.to_owned(),
+                ..err
+            }
+        }
+    })
+}
+
+/// Decodes `%XX` escapes and `+` as a space, as produced by form encoders.
+/// Returns `None` for malformed escapes or invalid UTF-8.
+fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            let mut decoded = [0u8; 1];
+
+            hex::decode_to_slice(hex, &mut decoded).ok()?;
+            bytes.push(decoded[0]);
+        } else if b == b'+' {
+            bytes.push(b' ');
+        } else {
+            bytes.push(b);
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+/// Checks only the CRC16 checksum of a Base64 address, ignoring its flags and workchain.
+///
+/// An error is returned if the address can't be decoded at all (wrong length,
+/// invalid characters), while a decodable address with a wrong checksum gives `Ok(false)`.
+pub fn crc_is_valid(address: &str) -> Result<bool, ParseError> {
+    let (bytes, _) = Address::decode_base64_to_buffer(address.trim_ascii(), None)?;
+
+    let server_crc = crc16(&bytes[0..34]);
+    let client_crc = ((bytes[34] as u16) << 8) | (bytes[35] as u16);
+
+    Ok(server_crc == client_crc)
+}
+
+/// The kind of difference between two Base64 addresses, see [`diff_kind`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AddressDiff {
+    /// Same account, flags and alphabet.
+    Identical,
+    /// Same account and flags, encoded with different alphabets.
+    AlphabetOnly,
+    /// Same account and alphabet, different bounceable or testnet flags.
+    FlagsOnly,
+    /// Same account, different flags and alphabets.
+    AlphabetAndFlags,
+    /// Different workchains or hash parts.
+    DifferentAccount,
+}
+
+/// Parses two Base64 addresses and categorizes their difference,
+/// e.g. to explain why two strings of the same account look different.
+///
+/// Alphanumeric addresses fit both alphabets, so alphabets only differ
+/// if the strings have no alphabet in common (see [`Base64Decoder::compatible_alphabets`]).
+pub fn diff_kind(a: &str, b: &str) -> Result<AddressDiff, ParseError> {
+    let (a, b) = (a.trim_ascii(), b.trim_ascii());
+    let (first, second) = (
+        Address::from_base64(a, None)?,
+        Address::from_base64(b, None)?,
+    );
+
+    if first.address != second.address {
+        return Ok(AddressDiff::DifferentAccount);
+    }
+
+    let compatible = Base64Decoder::compatible_alphabets(b);
+    let same_alphabet = Base64Decoder::compatible_alphabets(a)
+        .iter()
+        .any(|alphabet| compatible.contains(alphabet));
+    let same_flags = first.flags() == second.flags();
+
+    Ok(match (same_alphabet, same_flags) {
+        (true, true) => AddressDiff::Identical,
+        (false, true) => AddressDiff::AlphabetOnly,
+        (true, false) => AddressDiff::FlagsOnly,
+        (false, false) => AddressDiff::AlphabetAndFlags,
+    })
+}
+
+/// Checks whether two address strings in any of the forms accepted by [`FromStr`]
+/// point to the same account, i.e. have equal workchain and hash part.
+///
+/// The friendly flags and the alphabet are ignored. The first parse error is returned.
+pub fn is_same_account(a: &str, b: &str) -> Result<bool, ParseError> {
+    Ok(a.parse::<Address>()? == b.parse::<Address>()?)
+}
+
+/// Parses every address string and returns the unique accounts
+/// in the order they were first seen.
+///
+/// Addresses are compared by workchain and hash part only, so different
+/// forms (raw, bounceable, non-bounceable, etc.) of the same account
+/// are collapsed into one. The first parse error is returned.
+pub fn unique_accounts<I: IntoIterator<Item = S>, S: AsRef<str>>(
+    iter: I,
+) -> Result<Vec<Address>, ParseError> {
+    let mut seen = HashSet::new();
+    let mut accounts = Vec::new();
+
+    for s in iter {
+        let address = s.as_ref().parse::<Address>()?;
+
+        if seen.insert(address.clone()) {
+            accounts.push(address);
+        }
+    }
+
+    Ok(accounts)
+}
+
+/// An extension trait for iterators over address strings.
+pub trait AddressIteratorExt: Iterator + Sized
+where
+    Self::Item: AsRef<str>,
+{
+    /// Maps every address string in any of the forms accepted by [`FromStr`]
+    /// to its canonical form, see [`Address::normalize`].
+    fn normalize_addresses(self) -> NormalizeAddresses<Self> {
+        NormalizeAddresses { iter: self }
+    }
+}
+
+impl<I: Iterator> AddressIteratorExt for I where I::Item: AsRef<str> {}
+
+/// An iterator yielding canonical address strings,
+/// see [`AddressIteratorExt::normalize_addresses`].
+#[derive(Debug, Clone)]
+pub struct NormalizeAddresses<I> {
+    iter: I,
+}
+
+impl<I: Iterator> Iterator for NormalizeAddresses<I>
+where
+    I::Item: AsRef<str>,
+{
+    type Item = Result<String, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|s| Address::normalize(s.as_ref()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// A Bloom filter of accounts for memory-efficient allowlist checks, keyed on the
+/// 34-byte payload of the address (see [`Address::payload_bytes`]) with the
+/// default flags, so every form of the same account is treated as one entry.
+///
+/// A Bloom filter has false positives but no false negatives: [`AddressBloom::contains`]
+/// always returns `true` for inserted addresses, but may also return `true` for
+/// other addresses with roughly the false positive rate passed to [`AddressBloom::new`].
+/// Confirm positive results against the exact set if that matters.
+#[cfg(feature = "bloom")]
+#[derive(Debug, Clone)]
+pub struct AddressBloom {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+#[cfg(feature = "bloom")]
+impl AddressBloom {
+    /// Creates an empty filter sized for `expected_items` addresses
+    /// and the desired `false_positive_rate` (e.g. `0.01`).
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        let n = expected_items.max(1) as f64;
+        let p = false_positive_rate.clamp(f64::MIN_POSITIVE, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+
+        let num_bits = ((-n * p.ln() / (ln2 * ln2)).ceil() as u64).max(64);
+        let num_hashes = ((num_bits as f64 / n * ln2).round() as u32).max(1);
+
+        Self {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    /// Adds the account to the filter.
+    pub fn insert(&mut self, address: &Address) {
+        for index in self.indexes(address) {
+            self.bits[(index / 64) as usize] |= 1 << (index % 64);
+        }
+    }
+
+    /// Checks whether the account may have been inserted into the filter.
+    ///
+    /// `false` is always correct, `true` may be a false positive.
+    pub fn contains(&self, address: &Address) -> bool {
+        self.indexes(address)
+            .all(|index| self.bits[(index / 64) as usize] & (1 << (index % 64)) != 0)
+    }
+
+    /// Yields the bit indexes of the address using double hashing.
+    fn indexes(&self, address: &Address) -> impl Iterator<Item = u64> {
+        let hash = |seed: u8| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            seed.hash(&mut hasher);
+            address.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let (h1, h2) = (hash(0), hash(1));
+        let num_bits = self.num_bits;
+
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_address() {
+        let bytes = hex::decode("e4d954ef9f4e1250a26b5bbad76a1cdd17cfd08babad6f4c23e372270aef6f76")
+            .unwrap();
+        let hash_part: HashPart = bytes.as_slice().try_into().unwrap();
+        let workchain = 0;
+
+        let address = Address::new(workchain, &hash_part);
+        assert_eq!(address.get_workchain(), workchain);
+        assert_eq!(
+            address.get_hash_part(),
+            &[
+                0xe4, 0xd9, 0x54, 0xef, 0x9f, 0x4e, 0x12, 0x50, 0xa2, 0x6b, 0x5b, 0xba, 0xd7, 0x6a,
+                0x1c, 0xdd, 0x17, 0xcf, 0xd0, 0x8b, 0xab, 0xad, 0x6f, 0x4c, 0x23, 0xe3, 0x72, 0x27,
+                0x0a, 0xef, 0x6f, 0x76
+            ]
+        );
+    }
+
+    #[test]
+    fn test_new_address_empty() {
+        let address = Address::empty();
+
+        assert_eq!(address.get_workchain(), 0);
+        assert_eq!(address.get_hash_part(), &[0u8; 32]);
+    }
+
+    #[test]
+    fn test_workchain_id() {
+        // case (1): masterchain
+        {
+            let workchain = WorkchainId::new(-1).unwrap();
+
+            assert_eq!(workchain, WorkchainId::MASTERCHAIN);
+            assert_eq!(workchain.get(), -1);
+            assert_eq!(workchain.to_string(), "-1");
+            assert_eq!("-1".parse::<WorkchainId>(), Ok(workchain));
+        }
+
+        // case (2): basechain
+        {
+            let workchain = WorkchainId::new(0).unwrap();
+
+            assert_eq!(workchain, WorkchainId::BASECHAIN);
+            assert_eq!(workchain.get(), 0);
+            assert_eq!(workchain.to_string(), "0");
+            assert_eq!("0".parse::<WorkchainId>(), Ok(workchain));
+
+            let address = Address::new_checked(workchain, &[0xAB; 32]);
+            assert_eq!(address, Address::new(0, &[0xAB; 32]));
+        }
+
+        // case (3): out of range
+        {
+            assert_eq!(WorkchainId::new(128), None);
+            assert_eq!(WorkchainId::new(-129), None);
+            assert_eq!(
+                "300".parse::<WorkchainId>(),
+                Err(ParseError {
+                    address: "300".to_owned(),
+                    reason:
+                        "Invalid workchain string: workchain number must be in range -128..=127",
+                    offset: None,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_new_address_from_raw_adress() {
+        // main case
+        {
+            let raw_address = "0:e4d954ef9f4e1250a26b5bbad76a1cdd17cfd08babad6f4c23e372270aef6f76";
+            let address = Address::from_raw_address(raw_address);
+
+            assert_eq!(
+                address,
+                Ok(Address::new(
+                    0,
+                    &[
+                        0xe4, 0xd9, 0x54, 0xef, 0x9f, 0x4e, 0x12, 0x50, 0xa2, 0x6b, 0x5b, 0xba,
+                        0xd7, 0x6a, 0x1c, 0xdd, 0x17, 0xcf, 0xd0, 0x8b, 0xab, 0xad, 0x6f, 0x4c,
+                        0x23, 0xe3, 0x72, 0x27, 0x0a, 0xef, 0x6f, 0x76
+                    ]
+                ))
+            );
+        }
+
+        // error cases
+        {
+            let raw_address = "bad_string";
+            let address = Address::from_raw_address(raw_address);
+
+            assert_eq!(
+                address,
+                Err(ParseError {
+                    address: raw_address.to_owned(),
+                    reason: "Invalid raw address string: wrong address format",
+                    offset: None,
+                })
+            );
+        }
+
+        {
+            let raw_address = "fdfd:fdfd";
+            let address = Address::from_raw_address(raw_address);
+
+            assert_eq!(
+                address,
+                Err(ParseError {
+                    address: raw_address.to_owned(),
+                    reason: "Invalid raw address string: workchain number is not a 32-bit integer",
+                    offset: None,
+                })
+            );
+        }
+
+        {
+            let raw_address = "0:][p][;cr3244";
+            let address = Address::from_raw_address(raw_address);
+
+            assert_eq!(
+                address,
+                Err(ParseError {
+                    address: raw_address.to_owned(),
+                    reason: "Invalid raw address string: failed to decode hash part",
+                    offset: None,
+                })
+            );
+        }
+
+        {
+            let raw_address = "0:ABCDE012";
+            let address = Address::from_raw_address(raw_address);
+
+            assert_eq!(
+                address,
+                Err(ParseError {
+                    address: raw_address.to_owned(),
+                    reason: "Invalid raw address string: hash part length must be 32 bytes",
+                    offset: None,
+                })
+            );
+        }
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
+    fn test_from_base64() {
+        // main case (1): [bounceable] + [production] + [encoder guessing]
+        {
+            let result =
+                Address::from_base64("EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR", None)
+                    .unwrap();
+
+            // Encoder result
+            assert_eq!(result.is_bounceable(), true);
+            assert_eq!(result.is_production(), true);
+            assert_eq!(result.decoder, Base64Decoder::UrlSafe);
+
+            // Address
+            assert_eq!(result.address.get_workchain(), 0);
+            assert_eq!(
+                result.address.get_hash_part(),
+                &[
+                    228, 217, 84, 239, 159, 78, 18, 80, 162, 107, 91, 186, 215, 106, 28, 221, 23,
+                    207, 208, 139, 171, 173, 111, 76, 35, 227, 114, 39, 10, 239, 111, 118
+                ]
+            );
+        }
+
+        // main case (2): [non bounceable] + [production] + [encoder guessing]
+        {
+            let result =
+                Address::from_base64("UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t", None)
+                    .unwrap();
+
+            // Encoder result
+            assert_eq!(result.is_bounceable(), false);
+            assert_eq!(result.is_production(), true);
+            assert_eq!(result.decoder, Base64Decoder::Standard);
+
+            // Address
+            assert_eq!(result.address.get_workchain(), 0);
+            assert_eq!(
+                result.address.get_hash_part(),
+                &[
+                    22u8, 204, 66, 156, 118, 124, 164, 189, 119, 212, 54, 139, 170, 117, 46, 182,
+                    182, 250, 233, 223, 102, 194, 198, 226, 146, 233, 228, 43, 75, 162, 18, 129
+                ]
+            );
+        }
+
+        // main case (3): [bounceable] + [testnet] + [encoder guessing]
+        {
+            let result =
+                Address::from_base64("kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8", None)
+                    .unwrap();
+
+            // Encoder result
+            assert!(result.is_bounceable());
+            assert!(!result.is_production());
+            assert_eq!(result.decoder, Base64Decoder::UrlSafe);
+
+            // Address
+            assert_eq!(
+                result.address.to_raw_address(),
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            );
+            assert_eq!(
+                result.address.to_base64(Base64Encoder::UrlSafe {
+                    bounceable: true,
+                    production: false,
+                }),
+                "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8"
+            );
+        }
+
+        // main case (4): [non bounceable] + [testnet] + [encoder guessing]
+        {
+            let result =
+                Address::from_base64("0QAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJgI5", None)
+                    .unwrap();
+
+            // Encoder result
+            assert!(!result.is_bounceable());
+            assert!(!result.is_production());
+            assert_eq!(result.decoder, Base64Decoder::Standard);
+
+            // Address
+            assert_eq!(
+                result.address.to_raw_address(),
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            );
+            assert_eq!(
+                result.address.to_base64(Base64Encoder::Standard {
+                    bounceable: false,
+                    production: false,
+                }),
+                "0QAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJgI5"
+            );
+        }
+
+        // error case (1): bad length
+        {
+            let result = Address::from_base64("bad length", None);
+            assert_eq!(
+                result,
+                Err(ParseError {
+                    address: "bad length".to_owned(),
+                    reason: "Invalid base64 address string: length must be 48 characters",
+                    offset: None,
+                })
+            );
+        }
+
+        // error case (2): byte length
+        {
+            let result =
+                Address::from_base64("EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrRIyM", None);
+            assert_eq!(
+                result,
+                Err(ParseError {
+                    address: "EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrRIyM".to_owned(),
+                    reason: "Invalid base64 address string: length must be 48 characters",
+                    offset: None,
+                })
+            );
+        }
+
+        // error case (3): invalid flag
+        {
+            let result =
+                Address::from_base64("VQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR", None);
+            assert_eq!(
+                result,
+                Err(ParseError {
+                    address: "VQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR".to_owned(),
+                    reason: "Invalid base64 address string: invalid flag",
+                    offset: Some(0),
+                })
+            );
+        }
+
+        // error case (3): bad CRC16
+        {
+            let result =
+                Address::from_base64("EQDkqlTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR", None);
+            assert_eq!(
+                result,
+                Err(ParseError {
+                    address: "EQDkqlTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR".to_owned(),
+                    reason: "Invalid base64 address string: CRC16 hashes do not match",
+                    offset: Some(34),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare_addresses() {
+        // case (1): same addresses
+        {
+            let address1 =
+                Address::from_base64("UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t", None)
+                    .unwrap()
+                    .address;
+
+            let address2 =
+                Address::from_base64("UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t", None)
+                    .unwrap()
+                    .address;
+
+            assert_eq!(address1, address2);
+        }
+
+        // case (2): not same
+        {
+            let address1 =
+                Address::from_base64("UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t", None)
+                    .unwrap()
+                    .address;
+
+            let address2 =
+                Address::from_base64("EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR", None)
+                    .unwrap()
+                    .address;
+
+            assert_ne!(address1, address2);
+        }
+    }
+
+    #[test]
+    fn test_multi_converts() {
+        // case (1): from base64 url safe
+        {
+            let addr = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+                .parse::<Address>()
+                .unwrap();
+
+            assert_eq!(
+                addr.to_raw_address(),
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            );
+            assert_eq!(
+                addr.to_base64(BASE64_STD_DEFAULT),
+                "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2"
+            );
+            assert_eq!(
+                addr.to_base64(BASE64_URL_DEFAULT),
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            );
+            assert_eq!(
+                addr.to_string(),
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            );
+        }
+
+        // case (2): from base64 url std
+        {
+            let addr = "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2"
+                .parse::<Address>()
+                .unwrap();
+
+            assert_eq!(
+                addr.to_raw_address(),
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            );
+            assert_eq!(
+                addr.to_base64(BASE64_STD_DEFAULT),
+                "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2"
+            );
+            assert_eq!(
+                addr.to_base64(BASE64_URL_DEFAULT),
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            );
+            assert_eq!(
+                addr.to_string(),
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            );
+        }
+
+        // case (2): from raw address
+        {
+            let addr = "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+                .parse::<Address>()
+                .unwrap();
+
+            assert_eq!(
+                addr.to_raw_address(),
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            );
+            assert_eq!(
+                addr.to_base64(BASE64_STD_DEFAULT),
+                "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2"
+            );
+            assert_eq!(
+                addr.to_base64(BASE64_URL_DEFAULT),
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            );
+            assert_eq!(
+                addr.to_string(),
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            );
+        }
+    }
+
+    #[test]
+    fn test_address_flags() {
+        // case (1): tag bytes to flags
+        {
+            assert_eq!(
+                AddressFlags::from_tag_byte(0x11),
+                Some(AddressFlags::BOUNCEABLE)
+            );
+            assert_eq!(
+                AddressFlags::from_tag_byte(0x51),
+                Some(AddressFlags::empty())
+            );
+            assert_eq!(
+                AddressFlags::from_tag_byte(0x91),
+                Some(AddressFlags::BOUNCEABLE | AddressFlags::TESTNET)
+            );
+            assert_eq!(
+                AddressFlags::from_tag_byte(0xD1),
+                Some(AddressFlags::TESTNET)
+            );
+            assert_eq!(AddressFlags::from_tag_byte(0x00), None);
+        }
+
+        // case (2): flags to tag bytes
+        {
+            for tag in [0x11, 0x51, 0x91, 0xD1] {
+                let flags = AddressFlags::from_tag_byte(tag).unwrap();
+                assert_eq!(flags.to_tag_byte(), tag);
+            }
+        }
+
+        // case (3): encoder construction
+        {
+            let flags = AddressFlags::BOUNCEABLE | AddressFlags::TESTNET;
+            let encoder = Base64Encoder::url_safe(flags);
+
+            assert!(matches!(
+                encoder,
+                Base64Encoder::UrlSafe {
+                    bounceable: true,
+                    production: false
+                }
+            ));
+            assert_eq!(encoder.flags(), flags);
+        }
+
+        // case (4): flags of the decoded address
+        {
+            let result =
+                Address::from_base64("UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t", None)
+                    .unwrap();
+
+            assert_eq!(result.flags(), AddressFlags::empty());
+            assert_eq!(
+                result
+                    .address
+                    .to_base64(Base64Encoder::standard(result.flags())),
+                "UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t"
+            );
+        }
+    }
+
+    #[test]
+    fn test_address_into_string() {
+        let addr = "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            .parse::<Address>()
+            .unwrap();
+
+        let from_ref: String = (&addr).into();
+        assert_eq!(from_ref, addr.to_string());
+
+        let expected = addr.to_string();
+        let from_owned: String = addr.into();
+        assert_eq!(from_owned, expected);
+
+        let strings = vec![Address::empty()]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<String>>();
+        assert_eq!(strings, vec![Address::empty().to_string()]);
+    }
+
+    #[test]
+    fn test_hash_part_hex() {
+        let addr = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            .parse::<Address>()
+            .unwrap();
+
+        let raw_address = addr.to_raw_address();
+        let (_, hash) = raw_address.split_once(':').unwrap();
+
+        assert_eq!(addr.hash_part_hex().len(), 64);
+        assert_eq!(addr.hash_part_hex(), hash);
+        assert_eq!(addr.hash_part_hex_upper(), hash.to_uppercase());
+    }
+
+    #[test]
+    fn test_checksum() {
+        let addr = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            .parse::<Address>()
+            .unwrap();
+
+        for encoder in [
+            BASE64_URL_DEFAULT,
+            Base64Encoder::Standard {
+                bounceable: false,
+                production: false,
+            },
+        ] {
+            let bytes = addr.to_raw_bytes(encoder).unwrap();
+            let crc = ((bytes[34] as u16) << 8) | (bytes[35] as u16);
+
+            assert_eq!(addr.checksum(encoder).unwrap(), crc);
+        }
+
+        assert_eq!(
+            BASE64_URL_SAFE_NO_PAD.encode(addr.to_raw_bytes(BASE64_URL_DEFAULT).unwrap()),
+            addr.to_base64(BASE64_URL_DEFAULT)
+        );
+    }
+
+    #[test]
+    fn test_is_valid() {
+        // case (1): valid addresses
+        {
+            assert!(is_valid_base64(
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            ));
+            assert!(is_valid("EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2"));
+            assert!(is_valid(
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            ));
+        }
+
+        // case (2): bad CRC16
+        {
+            assert!(!is_valid_base64(
+                "EQDkqlTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR"
+            ));
+            assert!(!is_valid(
+                "EQDkqlTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR"
+            ));
+        }
+
+        // case (3): bad length
+        {
+            assert!(!is_valid_base64("bad length"));
+            assert!(!is_valid("bad length"));
+            assert!(!is_valid_base64(
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let expected = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2";
+
+        for address in [
+            "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2",
+            "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+            "UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz",
+            "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+        ] {
+            assert_eq!(Address::normalize(address), Ok(expected.to_owned()));
+        }
+
+        assert!(Address::normalize("bad string").is_err());
+    }
+
+    #[test]
+    fn test_display_alternate() {
+        let addr = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            .parse::<Address>()
+            .unwrap();
+
+        assert_eq!(format!("{:#}", addr), addr.to_raw_address());
+        assert_eq!(
+            format!("{}", addr),
+            "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+        );
+    }
+
+    #[test]
+    fn test_encoder_builder() {
+        // case (1): defaults
+        {
+            assert!(matches!(
+                Base64Encoder::builder().build(),
+                Base64Encoder::Standard {
+                    bounceable: true,
+                    production: true
+                }
+            ));
+        }
+
+        // case (2): url safe + non bounceable + testnet
+        {
+            assert!(matches!(
+                Base64Encoder::builder()
+                    .url_safe()
+                    .non_bounceable()
+                    .testnet()
+                    .build(),
+                Base64Encoder::UrlSafe {
+                    bounceable: false,
+                    production: false
+                }
+            ));
+        }
+
+        // case (3): the builder is reusable
+        {
+            let builder = Base64Encoder::builder().testnet();
+
+            assert!(matches!(
+                builder.url_safe().build(),
+                Base64Encoder::UrlSafe {
+                    bounceable: true,
+                    production: false
+                }
+            ));
+            assert!(matches!(
+                builder.non_bounceable().mainnet().build(),
+                Base64Encoder::Standard {
+                    bounceable: false,
+                    production: true
+                }
+            ));
+        }
+    }
+
+    #[test]
+    fn test_address_from_hash_part() {
+        let bytes = hex::decode("0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026")
+            .unwrap();
+        let hash_part: HashPart = bytes.as_slice().try_into().unwrap();
+
+        // case (1): basechain by default
+        {
+            let address = Address::from(hash_part);
+
+            assert_eq!(
+                address.to_raw_address(),
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            );
+        }
+
+        // case (2): explicit workchain
+        {
+            let address: Address = (-1, hash_part).into();
+
+            assert_eq!(
+                address.to_raw_address(),
+                "-1:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            );
+        }
+    }
+
+    #[test]
+    fn test_tag_byte() {
+        let cases = [
+            (
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+                true,
+                true,
+                0x11,
+            ),
+            (
+                "UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz",
+                false,
+                true,
+                0x51,
+            ),
+            (
+                "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8",
+                true,
+                false,
+                0x91,
+            ),
+            (
+                "0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5",
+                false,
+                false,
+                0xD1,
+            ),
+        ];
+
+        for (address, bounceable, production, tag) in cases {
+            let encoder = Base64Encoder::UrlSafe {
+                bounceable,
+                production,
+            };
+            assert_eq!(encoder.tag_byte(), tag);
+
+            let result = Address::from_base64(address, None).unwrap();
+            assert_eq!(result.tag_byte(), tag);
+        }
+    }
+
+    #[test]
+    fn test_decode_into_buffer() {
+        for address in [
+            "EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR",
+            "UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t",
+            "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8",
+            "0QAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJgI5",
+        ] {
+            let decoder = Base64Decoder::guess(address);
+            let expected = match decoder {
+                Base64Decoder::Standard => BASE64_STANDARD_NO_PAD.decode(address),
+                Base64Decoder::UrlSafe => BASE64_URL_SAFE_NO_PAD.decode(address),
+                Base64Decoder::Custom(_) => unreachable!(),
+            }
+            .unwrap();
+
+            assert_eq!(
+                decoder.decode_to_buffer(address).unwrap().as_slice(),
+                expected
+            );
+        }
+
+        // Strings that don't decode to exactly 36 bytes keep the old error
+        assert_eq!(
+            Base64Decoder::Standard
+                .decode_to_buffer("0QAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJgI5IyM"),
+            Err(ParseError {
+                address: "0QAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJgI5IyM".to_owned(),
+                reason: "Invalid base64 address string: length of decoded bytes must be 36",
+                offset: None,
+            })
+        );
+        assert_eq!(
+            Base64Decoder::UrlSafe
+                .decode_to_buffer("EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrRIyM"),
+            Err(ParseError {
+                address: "EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrRIyM".to_owned(),
+                reason: "Invalid base64 address string: length of decoded bytes must be 36",
+                offset: None,
+            })
+        );
+    }
+
+    #[cfg(feature = "rusqlite")]
+    #[test]
+    fn test_rusqlite() {
+        let connection = rusqlite::Connection::open_in_memory().unwrap();
+        connection
+            .execute("CREATE TABLE accounts (address TEXT NOT NULL)", ())
+            .unwrap();
+
+        let address = "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            .parse::<Address>()
+            .unwrap();
+
+        connection
+            .execute("INSERT INTO accounts (address) VALUES (?1)", [&address])
+            .unwrap();
+
+        let stored: String = connection
+            .query_row("SELECT address FROM accounts", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2");
+
+        let loaded: Address = connection
+            .query_row("SELECT address FROM accounts", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(loaded, address);
+
+        let invalid =
+            connection.query_row("SELECT 'bad string'", (), |row| row.get::<_, Address>(0));
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_parse_error_offset() {
+        // case (1): invalid flag
+        {
+            let err =
+                Address::from_base64("VQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR", None)
+                    .unwrap_err();
+
+            assert_eq!(err.offset, Some(0));
+            assert_eq!(
+                err.to_string(),
+                "Error parsing TON address: Invalid base64 address string: invalid flag (at byte offset 0)"
+            );
+        }
+
+        // case (2): bad CRC16
+        {
+            let err =
+                Address::from_base64("EQDkqlTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR", None)
+                    .unwrap_err();
+
+            assert_eq!(err.offset, Some(34));
+            assert_eq!(
+                err.to_string(),
+                "Error parsing TON address: Invalid base64 address string: CRC16 hashes do not match (at byte offset 34)"
+            );
+        }
+
+        // case (3): no offset
+        {
+            let err = Address::from_base64("bad length", None).unwrap_err();
+
+            assert_eq!(err.offset, None);
+            assert_eq!(
+                err.to_string(),
+                "Error parsing TON address: Invalid base64 address string: length must be 48 characters"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unique_accounts() {
+        // case (1): mixed forms of two accounts
+        {
+            let accounts = unique_accounts([
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+                "UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t",
+                "UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz",
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+            ])
+            .unwrap();
+
+            assert_eq!(accounts.len(), 2);
+            assert_eq!(
+                accounts[0].to_raw_address(),
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            );
+            assert_eq!(
+                accounts[1],
+                "UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t"
+                    .parse::<Address>()
+                    .unwrap()
+            );
+        }
+
+        // case (2): parse error
+        {
+            let result = unique_accounts(vec![
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2".to_owned(),
+                "bad string".to_owned(),
+            ]);
+
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn test_to_raw_address_upper() {
+        let raw_address = "-1:e4d954ef9f4e1250a26b5bbad76a1cdd17cfd08babad6f4c23e372270aef6f76";
+        let addr = Address::from_raw_address(raw_address).unwrap();
+
+        assert_eq!(addr.to_raw_address_upper(), raw_address.to_uppercase());
+        assert_eq!(addr.to_raw_address(), raw_address);
+    }
+
+    #[test]
+    fn test_from_raw_address_strictness() {
+        // case (1): surrounding whitespace
+        {
+            let address = Address::from_raw_address(
+                "  0:e4d954ef9f4e1250a26b5bbad76a1cdd17cfd08babad6f4c23e372270aef6f76\n",
+            )
+            .unwrap();
+
+            assert_eq!(
+                address.to_raw_address(),
+                "0:e4d954ef9f4e1250a26b5bbad76a1cdd17cfd08babad6f4c23e372270aef6f76"
+            );
+        }
+
+        // case (2): empty parts
+        {
+            assert_eq!(
+                Address::from_raw_address("0:"),
+                Err(ParseError {
+                    address: "0:".to_owned(),
+                    reason: "Invalid raw address string: hash part is empty",
+                    offset: None,
+                })
+            );
+            assert_eq!(
+                Address::from_raw_address(
+                    ":e4d954ef9f4e1250a26b5bbad76a1cdd17cfd08babad6f4c23e372270aef6f76"
+                )
+                .unwrap_err()
+                .reason,
+                "Invalid raw address string: workchain number is empty"
+            );
+        }
+
+        // case (3): double colon
+        {
+            assert_eq!(
+                Address::from_raw_address(
+                    "0::e4d954ef9f4e1250a26b5bbad76a1cdd17cfd08babad6f4c23e372270aef6f76"
+                )
+                .unwrap_err()
+                .reason,
+                "Invalid raw address string: more than one colon"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_base64_trims_whitespace() {
+        let expected = "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026";
+
+        let result =
+            Address::from_base64("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2\n", None)
+                .unwrap();
+        assert_eq!(result.address.to_raw_address(), expected);
+
+        let address = " EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2\t\r\n"
+            .parse::<Address>()
+            .unwrap();
+        assert_eq!(address.to_raw_address(), expected);
+
+        // Internal whitespace is not touched
+        assert!(
+            Address::from_base64("EQAOl3l3CEEcKaPLHz-BDvT4P0HZ kIOPf5POcILE_5qgJuR2", None)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_workchain_label() {
+        // case (1): masterchain (elector contract)
+        {
+            let address = "-1:3333333333333333333333333333333333333333333333333333333333333333"
+                .parse::<Address>()
+                .unwrap();
+
+            assert_eq!(address.workchain_label(), "masterchain");
+        }
+
+        // case (2): basechain
+        {
+            let address = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+                .parse::<Address>()
+                .unwrap();
+
+            assert_eq!(address.workchain_label(), "basechain");
+        }
+
+        // case (3): other workchains
+        {
+            let address = "7:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+                .parse::<Address>()
+                .unwrap();
+
+            assert_eq!(address.workchain_label(), "workchain 7");
+        }
+    }
+
+    #[test]
+    fn test_encoder_result_set_flags() {
+        let mut result =
+            Address::from_base64("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2", None).unwrap();
+        assert_eq!(
+            result.to_base64(),
+            "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+        );
+
+        result.set_bounceable(false);
+        assert!(result.is_non_bounceable());
+        assert_eq!(
+            result.to_base64(),
+            "UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz"
+        );
+
+        result.set_production(false);
+        assert!(result.is_non_production());
+        assert_eq!(
+            result.to_base64(),
+            "0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5"
+        );
+    }
+
+    #[test]
+    fn test_from_base64_bytes() {
+        // case (1): valid address
+        {
+            let bytes = b"EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2";
+            let result = Address::from_base64_bytes(bytes.as_slice(), None).unwrap();
+
+            assert!(result.is_bounceable());
+            assert_eq!(result.decoder, Base64Decoder::UrlSafe);
+            assert_eq!(
+                result.address,
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+                    .parse::<Address>()
+                    .unwrap()
+            );
+        }
+
+        // case (2): non-ASCII bytes
+        {
+            let result = Address::from_base64_bytes(&[0xFF; 48], None);
+
+            assert_eq!(
+                result.unwrap_err().reason,
+                "Invalid base64 address string: address contains non-ASCII characters"
+            );
+        }
+    }
+
+    #[test]
+    fn test_address_codec() {
+        /// Hex instead of base64, just to check the codec is used.
+        struct HexCodec;
+
+        impl AddressCodec for HexCodec {
+            fn encode(&self, buffer: &[u8; 36]) -> String {
+                hex::encode(buffer)
+            }
+
+            fn decode(&self, s: &str) -> Result<Vec<u8>, ParseError> {
+                hex::decode(s).map_err(|_| ParseError {
+                    address: s.to_owned(),
+                    reason: "bad hex",
+                    offset: None,
+                })
+            }
+        }
+
+        let address = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            .parse::<Address>()
+            .unwrap();
+        let flags = AddressFlags::TESTNET;
+
+        // case (1): custom codec
+        {
+            let encoded = address.to_base64_with_codec(flags, &HexCodec).unwrap();
+            assert_eq!(
+                encoded,
+                hex::encode(
+                    address
+                        .to_raw_bytes(Base64Encoder::standard(flags))
+                        .unwrap()
+                )
+            );
+
+            let (decoded, decoded_flags) =
+                Address::from_base64_with_codec(&encoded, &HexCodec).unwrap();
+            assert_eq!(decoded, address);
+            assert_eq!(decoded_flags, flags);
+
+            assert_eq!(
+                Address::from_base64_with_codec("abcd", &HexCodec)
+                    .unwrap_err()
+                    .reason,
+                "Invalid base64 address string: length of decoded bytes must be 36"
+            );
+        }
+
+        // case (2): built-in engines behave the same as the encoders
+        {
+            assert_eq!(
+                address
+                    .to_base64_with_codec(flags, &Base64Decoder::UrlSafe)
+                    .unwrap(),
+                address.to_base64(Base64Encoder::url_safe(flags))
+            );
+            assert_eq!(
+                Address::from_base64_with_codec(
+                    "0QAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJgI5",
+                    &Base64Decoder::Standard
+                ),
+                Ok((address, flags))
+            );
+        }
+    }
+
+    #[test]
+    fn test_custom_alphabet() {
+        // The url safe alphabet with `-` and `_` replaced by `.` and `~`
+        static ALPHABET: Alphabet =
+            match Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789.~")
+            {
+                Ok(alphabet) => alphabet,
+                Err(_) => panic!("invalid alphabet"),
+            };
+
+        let address = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            .parse::<Address>()
+            .unwrap();
+
+        let encoded = address.to_base64(Base64Encoder::Custom {
+            alphabet: &ALPHABET,
+            bounceable: true,
+            production: false,
+        });
+        assert_eq!(encoded, "kQAOl3l3CEEcKaPLHz.BDvT4P0HZkIOPf5POcILE~5qgJl~8");
+
+        let result =
+            Address::from_base64(&encoded, Some(Base64Decoder::Custom(&ALPHABET))).unwrap();
+        assert_eq!(result.address, address);
+        assert!(result.is_bounceable());
+        assert!(result.is_non_production());
+        assert_eq!(result.decoder, Base64Decoder::Custom(&ALPHABET));
+        assert_eq!(result.to_base64(), encoded);
+
+        assert!(Address::from_base64(&encoded, None).is_err());
+    }
+
+    #[test]
+    fn test_base64_round_trip_property() {
+        use proptest::prelude::*;
+        use proptest::test_runner::{Config, RngSeed, TestRunner};
+        use std::hash::{BuildHasher, RandomState};
+
+        // Use a random seed unless PROPTEST_RNG_SEED is set, so that
+        // a failure can be reproduced with the printed seed.
+        let seed = std::env::var("PROPTEST_RNG_SEED")
+            .ok()
+            .and_then(|seed| seed.parse::<u64>().ok())
+            .unwrap_or_else(|| RandomState::new().hash_one(0u8));
+
+        let mut runner = TestRunner::new(Config {
+            rng_seed: RngSeed::Fixed(seed),
+            failure_persistence: None,
+            ..Config::default()
+        });
+
+        let result = runner.run(
+            &(-128i32..=127, any::<HashPart>()),
+            |(workchain, hash_part)| {
+                let address = Address::new(workchain, &hash_part);
+
+                for bits in 0..4 {
+                    let flags = AddressFlags(bits);
+
+                    for alphabet in [Base64Decoder::Standard, Base64Decoder::UrlSafe] {
+                        let encoder = match alphabet {
+                            Base64Decoder::UrlSafe => Base64Encoder::url_safe(flags),
+                            _ => Base64Encoder::standard(flags),
+                        };
+                        let encoded = address.to_base64(encoder);
+
+                        let result = Address::from_base64(&encoded, Some(alphabet)).unwrap();
+                        prop_assert_eq!(result.address.get_workchain(), workchain);
+                        prop_assert_eq!(result.address.get_hash_part(), &hash_part);
+                        prop_assert_eq!(result.flags(), flags);
+
+                        let guessed = Address::from_base64(&encoded, None).unwrap();
+                        prop_assert_eq!(&guessed.address, &address);
+                        prop_assert_eq!(guessed.flags(), flags);
+                    }
+                }
+
+                Ok(())
+            },
+        );
+
+        if let Err(err) = result {
+            panic!("{err}\nreproduce with PROPTEST_RNG_SEED={seed}");
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_encoder_result_serde() {
+        for address in [
+            "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8",
+            "0QAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJgI5",
+        ] {
+            let result = Address::from_base64(address, None).unwrap();
+
+            let json = serde_json::to_string(&result).unwrap();
+            assert_eq!(json, format!("\"{address}\""));
+
+            let decoded: EncoderResult = serde_json::from_str(&json).unwrap();
+            assert_eq!(decoded, result);
+            assert_eq!(decoded.flags(), result.flags());
+            assert_eq!(decoded.decoder, result.decoder);
+            assert_eq!(decoded.to_base64(), address);
+        }
+
+        assert!(serde_json::from_str::<EncoderResult>("\"bad string\"").is_err());
+    }
+
+    #[test]
+    fn test_address_type_classify() {
+        // case (1): all four prefixes
+        {
+            let cases = [
+                (
+                    "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+                    AddressType::Bounceable,
+                ),
+                (
+                    "UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz",
+                    AddressType::NonBounceable,
+                ),
+                (
+                    "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8",
+                    AddressType::TestnetBounceable,
+                ),
+                (
+                    "0QAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJgI5",
+                    AddressType::TestnetNonBounceable,
+                ),
+            ];
+
+            for (address, expected) in cases {
+                assert_eq!(AddressType::classify(address), Ok(expected));
+                assert_eq!(AddressType::classify_strict(address), Ok(expected));
+                assert_eq!(AddressType::from_flags(expected.flags()), expected);
+            }
+
+            assert!(AddressType::TestnetBounceable.is_testnet());
+            assert!(AddressType::TestnetBounceable.is_bounceable());
+        }
+
+        // case (2): the non strict variant doesn't check CRC16
+        {
+            let address = "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_9";
+
+            assert_eq!(
+                AddressType::classify(address),
+                Ok(AddressType::TestnetBounceable)
+            );
+            assert_eq!(
+                AddressType::classify_strict(address).unwrap_err().reason,
+                "Invalid base64 address string: CRC16 hashes do not match"
+            );
+        }
+
+        // case (3): invalid flag
+        {
+            assert_eq!(
+                AddressType::classify("VQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR")
+                    .unwrap_err()
+                    .reason,
+                "Invalid base64 address string: invalid flag"
+            );
+        }
+    }
+
+    #[test]
+    fn test_packed_account_id() {
+        // case (1): masterchain
+        {
+            let address = "-1:3333333333333333333333333333333333333333333333333333333333333333"
+                .parse::<Address>()
+                .unwrap();
+            let (workchain, account_id) = address.to_packed();
+
+            assert_eq!(workchain, -1);
+            assert_eq!(account_id, [0x33; 32]);
+            assert_eq!(address.account_id_be(), account_id);
+            assert_eq!(Address::from_packed(workchain, account_id), address);
+        }
+
+        // case (2): basechain
+        {
+            let address = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+                .parse::<Address>()
+                .unwrap();
+            let (workchain, account_id) = address.to_packed();
+
+            assert_eq!(workchain, 0);
+            assert_eq!(account_id[0], 0x0e);
+            assert_eq!(account_id[31], 0x26);
+            assert_eq!(Address::from_packed(workchain, account_id), address);
+        }
+    }
+
+    #[test]
+    fn test_codec_from_str() {
+        // case (1): decoder names
+        {
+            for name in ["standard", "STD", "Standard"] {
+                assert_eq!(name.parse::<Base64Decoder>(), Ok(Base64Decoder::Standard));
+            }
+
+            for name in ["url", "UrlSafe", "url-safe"] {
+                assert_eq!(name.parse::<Base64Decoder>(), Ok(Base64Decoder::UrlSafe));
+            }
+
+            assert_eq!(
+                "base32".parse::<Base64Decoder>(),
+                Err(ParseError {
+                    address: "base32".to_owned(),
+                    reason: "Invalid alphabet string: unknown alphabet",
+                    offset: None,
+                })
+            );
+        }
+
+        // case (2): encoder with flags
+        {
+            assert!(matches!(
+                "std".parse::<Base64Encoder>(),
+                Ok(Base64Encoder::Standard {
+                    bounceable: true,
+                    production: true
+                })
+            ));
+            assert!(matches!(
+                "url:non-bounceable:testnet".parse::<Base64Encoder>(),
+                Ok(Base64Encoder::UrlSafe {
+                    bounceable: false,
+                    production: false
+                })
+            ));
+            assert!(matches!(
+                "URL:Testnet:Mainnet".parse::<Base64Encoder>(),
+                Ok(Base64Encoder::UrlSafe {
+                    bounceable: true,
+                    production: true
+                })
+            ));
+        }
+
+        // case (3): invalid encoder strings
+        {
+            assert_eq!(
+                "hex:testnet".parse::<Base64Encoder>().unwrap_err().reason,
+                "Invalid encoder string: unknown alphabet"
+            );
+            assert_eq!(
+                "url:bouncy".parse::<Base64Encoder>().unwrap_err().reason,
+                "Invalid encoder string: unknown flag"
+            );
+        }
+    }
+
+    #[test]
+    fn test_codec_display() {
+        // case (1): decoders
+        {
+            assert_eq!(Base64Decoder::Standard.to_string(), "standard");
+            assert_eq!(Base64Decoder::UrlSafe.to_string(), "url-safe");
+            assert_eq!(
+                Base64Decoder::Custom(&base64::alphabet::BCRYPT).to_string(),
+                "custom"
+            );
+        }
+
+        // case (2): encoders
+        {
+            assert_eq!(
+                BASE64_STD_DEFAULT.to_string(),
+                "standard (bounceable, mainnet)"
+            );
+            assert_eq!(
+                Base64Encoder::UrlSafe {
+                    bounceable: false,
+                    production: false,
+                }
+                .to_string(),
+                "url-safe (non-bounceable, testnet)"
+            );
+            assert_eq!(
+                Base64Encoder::Custom {
+                    alphabet: &base64::alphabet::BCRYPT,
+                    bounceable: true,
+                    production: false,
+                }
+                .to_string(),
+                "custom (bounceable, testnet)"
+            );
+        }
+
+        // case (3): the alphabet name can be parsed back
+        {
+            for decoder in [Base64Decoder::Standard, Base64Decoder::UrlSafe] {
+                assert_eq!(decoder.to_string().parse::<Base64Decoder>(), Ok(decoder));
+            }
+        }
+    }
+
+    #[test]
+    fn test_encoder_result_address() {
+        let result =
+            Address::from_base64("kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8", None).unwrap();
+
+        let cloned_result = result.clone();
+        assert_eq!(cloned_result.flags(), result.flags());
+
+        let address = result.address().clone();
+        assert_eq!(&address, result.address());
+        assert_eq!(result.into_address(), address);
+        assert_eq!(cloned_result.into_address(), address);
+    }
+
+    #[test]
+    fn test_address_as_ref() {
+        fn hex_of(bytes: impl AsRef<[u8]>) -> String {
+            hex::encode(bytes)
+        }
+
+        let address = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            .parse::<Address>()
+            .unwrap();
+
+        assert_eq!(hex_of(&address), address.hash_part_hex());
+
+        let hash_part: &HashPart = address.as_ref();
+        assert_eq!(hash_part, address.get_hash_part());
+    }
+
+    #[test]
+    fn test_masterchain_and_basechain() {
+        // case (1): masterchain
+        {
+            let address = Address::masterchain(&[0x33; 32]);
+            assert_eq!(address.get_workchain(), -1);
+
+            let encoded = address.to_base64(BASE64_URL_DEFAULT);
+            assert_eq!(encoded, "Ef8zMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzM0vF");
+            assert_eq!(encoded.parse::<Address>(), Ok(address));
+        }
+
+        // case (2): basechain
+        {
+            let address = Address::basechain(&[0x33; 32]);
+            assert_eq!(address.get_workchain(), 0);
+
+            let encoded = address.to_base64(BASE64_URL_DEFAULT);
+            assert_eq!(encoded.parse::<Address>(), Ok(address));
+        }
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        // case (1): mixed lines
+        {
+            let input = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2\n\
+                \n\
+                  0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026  \r\n\
+                bad string\n";
+
+            let results = Address::parse_reader(std::io::Cursor::new(input)).collect::<Vec<_>>();
+
+            assert_eq!(results.len(), 3);
+            assert_eq!(results[0], results[1]);
+            assert!(results[0].is_ok());
+            assert_eq!(
+                results[2].as_ref().unwrap_err().reason,
+                "Invalid base64 address string: length must be 48 characters"
+            );
+        }
+
+        // case (2): a line that isn't valid UTF-8 is reported and skipped
+        {
+            let input: &[u8] = b"\xFF\xFE\nEQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2\n";
+            let results = Address::parse_reader(input).collect::<Vec<_>>();
+
+            assert_eq!(results.len(), 2);
+            assert_eq!(
+                results[0],
+                Err(ParseError {
+                    address: "\u{FFFD}\u{FFFD}".to_owned(),
+                    reason: "Invalid address stream: line is not valid UTF-8",
+                    offset: Some(1),
+                })
+            );
+            assert!(results[1].is_ok());
+        }
+
+        // case (3): a persistently failing reader ends the iterator after the first error
+        {
+            struct FailingReader;
+
+            impl Read for FailingReader {
+                fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                    Err(std::io::Error::from(std::io::ErrorKind::BrokenPipe))
+                }
+            }
+
+            let input =
+                std::io::Cursor::new("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2\n\n")
+                    .chain(FailingReader);
+            let results = Address::parse_reader(input).take(10).collect::<Vec<_>>();
+
+            assert_eq!(results.len(), 2);
+            assert!(results[0].is_ok());
+            assert_eq!(
+                results[1],
+                Err(ParseError {
+                    address: String::new(),
+                    reason: "Invalid address stream: failed to read line",
+                    offset: Some(3),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_encoder_result_eq_strict() {
+        let bounceable =
+            Address::from_base64("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2", None).unwrap();
+        let non_bounceable =
+            Address::from_base64("UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz", None).unwrap();
+        let standard =
+            Address::from_base64("EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2", None).unwrap();
+
+        assert!(bounceable == non_bounceable);
+        assert!(!bounceable.eq_strict(&non_bounceable));
+
+        assert!(bounceable == standard);
+        assert!(bounceable.eq_strict(&standard));
+    }
+
+    #[test]
+    fn test_from_str_in_workchains() {
+        // case (1): allowed workchain
+        {
+            let address = Address::from_str_in_workchains(
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+                &[0],
+            );
+
+            assert!(address.is_ok());
+        }
+
+        // case (2): masterchain is not allowed
+        {
+            let raw_address = "-1:3333333333333333333333333333333333333333333333333333333333333333";
+
+            assert_eq!(
+                Address::from_str_in_workchains(raw_address, &[0]),
+                Err(ParseError {
+                    address: raw_address.to_owned(),
+                    reason: "Invalid address: workchain is not allowed",
+                    offset: None,
+                })
+            );
+            assert!(Address::from_str_in_workchains(raw_address, &[0, -1]).is_ok());
+        }
+
+        // case (3): parse errors come first
+        {
+            assert_eq!(
+                Address::from_str_in_workchains("bad string", &[0])
+                    .unwrap_err()
+                    .reason,
+                "Invalid base64 address string: length must be 48 characters"
+            );
+        }
+    }
+
+    #[test]
+    fn test_network_and_bounce() {
+        let cases = [
+            (
+                Network::Mainnet,
+                Bounce::Bounceable,
+                0x11,
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+            ),
+            (
+                Network::Mainnet,
+                Bounce::NonBounceable,
+                0x51,
+                "UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz",
+            ),
+            (
+                Network::Testnet,
+                Bounce::Bounceable,
+                0x91,
+                "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8",
+            ),
+            (
+                Network::Testnet,
+                Bounce::NonBounceable,
+                0xD1,
+                "0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5",
+            ),
+        ];
+
+        for (network, bounce, tag, address) in cases {
+            let encoder = Base64Encoder::new(Base64Decoder::UrlSafe, network, bounce);
+            assert_eq!(encoder.tag_byte(), tag);
+            assert_eq!(encoder.alphabet(), Base64Decoder::UrlSafe);
+
+            let result = Address::from_base64(address, None).unwrap();
+            assert_eq!(result.network(), network);
+            assert_eq!(result.bounce(), bounce);
+            assert_eq!(result.address.to_base64(encoder), address);
+        }
+    }
+
+    #[test]
+    fn test_testnet_constants() {
+        let addr = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            .parse::<Address>()
+            .unwrap();
+
+        assert_eq!(
+            addr.to_base64(BASE64_URL_TESTNET),
+            "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8"
+        );
+        assert_eq!(
+            addr.to_base64(BASE64_STD_TESTNET),
+            "kQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJl/8"
+        );
+        assert_eq!(
+            addr.to_base64(BASE64_URL_TESTNET_NON_BOUNCEABLE),
+            "0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5"
+        );
+        assert_eq!(
+            addr.to_base64(BASE64_STD_TESTNET_NON_BOUNCEABLE),
+            "0QAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJgI5"
+        );
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            detect_format("0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"),
+            AddressFormat::Raw
+        );
+        assert_eq!(
+            detect_format("EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2"),
+            AddressFormat::Base64Standard
+        );
+        assert_eq!(
+            detect_format("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"),
+            AddressFormat::Base64UrlSafe
+        );
+        assert_eq!(detect_format("bad string"), AddressFormat::Unknown);
+    }
+
+    #[test]
+    fn test_parse_with_format() {
+        let expected = "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            .parse::<Address>()
+            .unwrap();
+
+        let cases = [
+            (
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+                AddressFormat::Raw,
+            ),
+            (
+                "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2",
+                AddressFormat::Base64Standard,
+            ),
+            (
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+                AddressFormat::Base64UrlSafe,
+            ),
+        ];
+
+        for (address, format) in cases {
+            assert_eq!(Address::parse(address), Ok((expected.clone(), format)));
+        }
+
+        assert!(Address::parse("bad string").is_err());
+    }
+
+    #[test]
+    fn test_has_friendly_prefix() {
+        let addr = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+            .parse::<Address>()
+            .unwrap();
+
+        // case (1): case-sensitive
+        {
+            assert!(addr.has_friendly_prefix("EQAOl3l3", BASE64_URL_DEFAULT));
+            assert!(addr.has_friendly_prefix("", BASE64_URL_DEFAULT));
+            assert!(addr.has_friendly_prefix("kQAO", BASE64_URL_TESTNET));
+            assert!(!addr.has_friendly_prefix("EQAOL3L3", BASE64_URL_DEFAULT));
+            assert!(!addr.has_friendly_prefix("UQAO", BASE64_URL_DEFAULT));
+        }
+
+        // case (2): case-insensitive
+        {
+            assert!(addr.has_friendly_prefix_ignore_case("eqaol3L3", BASE64_URL_DEFAULT));
+            assert!(!addr.has_friendly_prefix_ignore_case("uqao", BASE64_URL_DEFAULT));
+            assert!(!addr.has_friendly_prefix_ignore_case(&"E".repeat(49), BASE64_URL_DEFAULT));
+        }
+    }
+
+    #[test]
+    fn test_is_same_account() {
+        // case (1): bounceable and non-bounceable forms of one account
+        {
+            assert_eq!(
+                is_same_account(
+                    "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+                    "UQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJrmz"
+                ),
+                Ok(true)
+            );
+        }
+
+        // case (2): different accounts
+        {
+            assert_eq!(
+                is_same_account(
+                    "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+                    "UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t"
+                ),
+                Ok(false)
+            );
+        }
+
+        // case (3): the first error is returned
+        {
+            assert_eq!(
+                is_same_account("0:", "bad string").unwrap_err().reason,
+                "Invalid raw address string: hash part is empty"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_many() {
+        let results = validate_many([
+            "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+            "bad string",
+            "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+            "0:",
+        ]);
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(
+            results
+                .iter()
+                .filter(|(_, result)| result.is_err())
+                .map(|(index, _)| *index)
+                .collect::<Vec<usize>>(),
+            vec![1, 3]
+        );
+        assert_eq!(
+            results[3].1.as_ref().unwrap_err().reason,
+            "Invalid raw address string: hash part is empty"
+        );
+        assert_eq!(results[0].1, results[2].1);
+    }
+
+    #[test]
+    fn test_parse_entry_points_are_consistent() {
+        let inputs = [
+            "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+            "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2\n",
+            "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+            "0:0E97797708411C29A3CB1F3F810EF4F83F41D990838F7F93CE7082C4FF9AA026",
+            "bad string",
+            "0:][p][;cr3244",
+            "0:ABCDE012",
+            "0:abc",
+            "0::00",
+        ];
+
+        for input in inputs {
+            let from_str = input.parse::<Address>();
+            let try_from = Address::try_from(input.to_owned());
+
+            assert_eq!(from_str, try_from);
+        }
+
+        assert_eq!(
+            Address::from_raw_address("0:abc").unwrap_err().reason,
+            "Invalid raw address string: failed to decode hash part"
+        );
+        assert_eq!(
+            Address::from_raw_address("0:abcd").unwrap_err().reason,
+            "Invalid raw address string: hash part length must be 32 bytes"
+        );
+    }
+
+    #[test]
+    fn test_workchain_id_from_str() {
+        // case (1): valid workchains
+        {
+            assert_eq!("-1".parse::<WorkchainId>(), Ok(WorkchainId::MASTERCHAIN));
+            assert_eq!("0".parse::<WorkchainId>(), Ok(WorkchainId::BASECHAIN));
+            assert_eq!("127".parse::<WorkchainId>().map(|wc| wc.get()), Ok(127));
+            assert_eq!("-128".parse::<WorkchainId>().map(|wc| wc.get()), Ok(-128));
+        }
+
+        // case (2): out of the byte range
+        {
+            assert_eq!(
+                "128".parse::<WorkchainId>(),
+                Err(ParseError {
+                    address: "128".to_owned(),
+                    reason:
+                        "Invalid workchain string: workchain number must be in range -128..=127",
+                    offset: None,
+                })
+            );
+        }
+
+        // case (3): not a number (U+2212 minus sign)
+        {
+            assert_eq!(
+                "\u{2212}1".parse::<WorkchainId>().unwrap_err().reason,
+                "Invalid workchain string: workchain number is not a 32-bit integer"
+            );
+        }
+    }
+
+    #[test]
+    fn test_raw_to_friendly() {
+        let raw = "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026";
+
+        let cases = [
+            (
+                BASE64_URL_DEFAULT,
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+            ),
+            (
+                Base64Encoder::UrlSafe {
+                    bounceable: false,
+                    production: true,
+                },
+                "UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz",
+            ),
+            (
+                BASE64_URL_TESTNET,
+                "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8",
+            ),
+            (
+                BASE64_STD_TESTNET_NON_BOUNCEABLE,
+                "0QAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJgI5",
+            ),
+        ];
+
+        for (encoder, expected) in cases {
+            assert_eq!(raw_to_friendly(raw, encoder), Ok(expected.to_owned()));
+        }
+
+        assert!(raw_to_friendly(
+            "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+            BASE64_URL_DEFAULT
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_from_base64_non_ascii() {
+        // Full-width "Ｅ" instead of "E"
+        let address = "ＥQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2";
+
+        assert_eq!(
+            Address::from_base64(address, None),
+            Err(ParseError {
+                address: address.to_owned(),
+                reason: "Invalid base64 address string: address contains non-ASCII characters",
+                offset: None,
+            })
+        );
+        assert_eq!(
+            address.parse::<Address>().unwrap_err().reason,
+            "Invalid base64 address string: address contains non-ASCII characters"
+        );
+    }
+
+    #[test]
+    fn test_from_base64_lenient() {
+        let expected =
+            Address::from_base64("kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8", None).unwrap();
+
+        for address in [
+            "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8",
+            "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8==",
+            "  kQAOl3l3CEEc KaPLHz-BDvT4\nP0HZkIOPf5POcILE_5qgJl_8 = \n",
+        ] {
+            let result = Address::from_base64_lenient(address, None).unwrap();
+
+            assert!(result.eq_strict(&expected));
+            assert_eq!(result.decoder, expected.decoder);
+        }
+
+        // The strict version stays strict
+        assert!(
+            Address::from_base64("kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8==", None)
+                .is_err()
+        );
+
+        // Errors report the original input
+        assert_eq!(
+            Address::from_base64_lenient("bad = string", None),
+            Err(ParseError {
+                address: "bad = string".to_owned(),
+                reason: "Invalid base64 address string: length must be 48 characters",
+                offset: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_encoder_result_hash() {
+        let mut set = HashSet::new();
+
+        set.insert(
+            Address::from_base64("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2", None).unwrap(),
+        );
+        set.insert(
+            Address::from_base64("UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz", None).unwrap(),
+        );
+        assert_eq!(set.len(), 1);
+
+        set.insert(
+            Address::from_base64("UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t", None).unwrap(),
+        );
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_extended_encoding() {
+        let hash_part = [0x33u8; 32];
+
+        // case (1): a workchain that doesn't fit in a byte survives the round trip
+        for workchain in [1000, -70000, i32::MAX, i32::MIN] {
+            for alphabet in [Base64Decoder::Standard, Base64Decoder::UrlSafe] {
+                let encoder = Base64Encoder::Extended {
+                    alphabet,
+                    bounceable: false,
+                    production: false,
+                };
+
+                let address = Address::new(workchain, &hash_part);
+                let encoded = address.to_base64(encoder);
+                assert_eq!(encoded.len(), 52);
+
+                let result = Address::from_base64_extended(&encoded, None).unwrap();
+                assert_eq!(result.address, address);
+                assert!(result.non_bounceable);
+                assert!(result.non_production);
+                assert!(result.extended);
+                assert!(address.has_friendly_prefix(&encoded[..10], encoder));
+
+                // the result is encoded back to the same extended string
+                assert_eq!(result.to_base64(), encoded);
+
+                let restored = EncoderResult::from_token(&result.to_token()).unwrap();
+                assert!(restored.extended);
+                assert_eq!(restored.to_base64(), encoded);
+            }
+        }
+
+        // case (2): tag byte and display
+        let encoder = Base64Encoder::Extended {
+            alphabet: Base64Decoder::UrlSafe,
+            bounceable: true,
+            production: true,
+        };
+        assert_eq!(encoder.tag_byte(), 0x12);
+        assert_eq!(
+            encoder.to_string(),
+            "extended url-safe (bounceable, mainnet)"
+        );
+
+        // case (3): extended and standard addresses aren't interchangeable
+        let address = Address::new(1000, &hash_part);
+        let extended = address.to_base64(encoder);
+        assert!(Address::from_base64(&extended, None).is_err());
+
+        let standard = address.to_base64(BASE64_URL_DEFAULT);
+        assert!(Address::from_base64_extended(&standard, None).is_err());
+
+        // case (4): broken checksum
+        let mut broken = extended.into_bytes();
+        broken[51] = if broken[51] == b'A' { b'B' } else { b'A' };
+        let broken = String::from_utf8(broken).unwrap();
+
+        assert_eq!(
+            Address::from_base64_extended(&broken, None),
+            Err(ParseError {
+                address: broken.clone(),
+                reason: "Invalid base64 address string: CRC16 hashes do not match",
+                offset: Some(37),
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_extended_encoder_result_serde() {
+        let encoded = Address::new(1000, &[0x33; 32]).to_base64(Base64Encoder::Extended {
+            alphabet: Base64Decoder::UrlSafe,
+            bounceable: true,
+            production: false,
+        });
+        let result = Address::from_base64_extended(&encoded, None).unwrap();
+
+        // case (1): the full workchain survives serialization
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(json, format!("\"{encoded}\""));
+
+        let deserialized = serde_json::from_str::<EncoderResult>(&json).unwrap();
+        assert!(deserialized.eq_strict(&result));
+        assert!(deserialized.extended);
+        assert_eq!(deserialized.address.get_workchain(), 1000);
+    }
+
+    #[test]
+    fn test_raw_bytes_layout_errors() {
+        // case (1): the extended encoder doesn't use the 36-byte layout
+        {
+            let address = Address::empty();
+            let encoder = Base64Encoder::Extended {
+                alphabet: Base64Decoder::UrlSafe,
+                bounceable: true,
+                production: true,
+            };
+            let reason = "Invalid encoder: extended addresses don't have the 36-byte layout";
+
+            assert_eq!(
+                address.to_raw_bytes(encoder),
+                Err(ParseError {
+                    address: address.to_raw_address(),
+                    reason,
+                    offset: None,
+                })
+            );
+            assert_eq!(address.checksum(encoder).unwrap_err().reason, reason);
+            assert_eq!(address.payload_bytes(encoder).unwrap_err().reason, reason);
+        }
+
+        // case (2): a workchain that doesn't fit in a single byte
+        {
+            let address = Address::new(1000, &[0x33; 32]);
+            let reason = "Invalid workchain: the workchain doesn't fit in a single byte";
+
+            assert_eq!(
+                address.to_raw_bytes(BASE64_URL_DEFAULT),
+                Err(ParseError {
+                    address: address.to_raw_address(),
+                    reason,
+                    offset: None,
                 })
             );
+            assert_eq!(
+                address
+                    .to_base64_with_codec(AddressFlags::BOUNCEABLE, &Base64Decoder::UrlSafe)
+                    .unwrap_err()
+                    .reason,
+                reason
+            );
+
+            let mut out = [0u8; 48];
+            assert_eq!(
+                address
+                    .write_base64(BASE64_URL_DEFAULT, &mut out)
+                    .unwrap_err()
+                    .reason,
+                reason
+            );
+        }
+    }
+
+    #[test]
+    fn test_borrow_lookup() {
+        use std::collections::HashMap;
+
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): an owned address key is found by a borrowed reference
+        let mut balances = HashMap::new();
+        balances.insert(address.clone(), 42u64);
+
+        let address_ref = &address;
+        assert_eq!(balances.get(address_ref), Some(&42));
+
+        // case (2): an encoder result key is found by a borrowed address,
+        // regardless of the flags it was decoded with
+        let mut results = HashMap::new();
+        let result =
+            Address::from_base64("0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5", None).unwrap();
+        results.insert(result, "testnet");
+
+        assert_eq!(results.get(address_ref), Some(&"testnet"));
+        assert_eq!(results.get(&Address::empty()), None);
+    }
+
+    #[test]
+    fn test_to_base64_all() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+        let all = address.to_base64_all();
+
+        // case (1): every form
+        assert_eq!(
+            all,
+            AddressRepresentations {
+                raw: "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+                    .to_owned(),
+                bounceable: "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2".to_owned(),
+                non_bounceable: "UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz".to_owned(),
+                testnet_bounceable: "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8".to_owned(),
+                testnet_non_bounceable: "0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5"
+                    .to_owned(),
+            }
+        );
+
+        // case (2): the rendered summary contains every form on its own line
+        let rendered = all.to_string();
+        assert_eq!(rendered.lines().count(), 5);
+
+        for prefix in ["0:0e97", "EQAO", "UQAO", "kQAO", "0QAO"] {
+            assert!(rendered.lines().any(|line| line
+                .split_ascii_whitespace()
+                .last()
+                .unwrap()
+                .starts_with(prefix)));
+        }
+    }
+
+    #[test]
+    fn test_eq_address_and_encoder_result() {
+        let hash_part =
+            hex::decode("0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026")
+                .unwrap();
+        let address = Address::new(0, hash_part.as_slice().try_into().unwrap());
+
+        // case (1): same account regardless of the flags
+        for encoded in [
+            "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+            "UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz",
+            "0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5",
+        ] {
+            let result = Address::from_base64(encoded, None).unwrap();
+
+            assert_eq!(result, address);
+            assert_eq!(address, result);
+        }
+
+        // case (2): different accounts
+        let result =
+            Address::from_base64("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2", None).unwrap();
+        assert_ne!(result, Address::empty());
+        assert_ne!(Address::empty(), result);
+    }
+
+    #[test]
+    fn test_token_round_trip() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): every flag combination and alphabet
+        for alphabet in [
+            Base64Decoder::Standard,
+            Base64Decoder::UrlSafe,
+            Base64Decoder::Custom(&base64::alphabet::CRYPT),
+        ] {
+            for network in [Network::Mainnet, Network::Testnet] {
+                for bounce in [Bounce::Bounceable, Bounce::NonBounceable] {
+                    let encoded = address.to_base64(Base64Encoder::new(alphabet, network, bounce));
+                    let result = Address::from_base64(&encoded, Some(alphabet)).unwrap();
+
+                    let restored = EncoderResult::from_token(&result.to_token()).unwrap();
+
+                    assert!(restored.eq_strict(&result));
+                    assert_eq!(restored.decoder, alphabet);
+                    assert_eq!(restored.to_base64(), encoded);
+                }
+            }
+        }
+
+        // case (2): the full workchain is preserved
+        let result = EncoderResult {
+            address: Address::new(1000, &[0x33; 32]),
+            non_bounceable: false,
+            non_production: false,
+            decoder: Base64Decoder::UrlSafe,
+            extended: false,
+        };
+        let restored = EncoderResult::from_token(&result.to_token()).unwrap();
+        assert_eq!(restored.address.get_workchain(), 1000);
+
+        // case (3): invalid tokens
+        assert_eq!(
+            EncoderResult::from_token("AAAA"),
+            Err(ParseError {
+                address: "AAAA".to_owned(),
+                reason: "Invalid token: invalid length",
+                offset: None,
+            })
+        );
+        assert_eq!(
+            EncoderResult::from_token("!!"),
+            Err(ParseError {
+                address: "!!".to_owned(),
+                reason: "Invalid token: base64 decode error",
+                offset: None,
+            })
+        );
+
+        let mut token = BASE64_URL_SAFE_NO_PAD.decode(result.to_token()).unwrap();
+        token[1] = 0x00;
+        let token = BASE64_URL_SAFE_NO_PAD.encode(token);
+
+        assert_eq!(
+            EncoderResult::from_token(&token),
+            Err(ParseError {
+                address: token.clone(),
+                reason: "Invalid token: invalid flag",
+                offset: Some(1),
+            })
+        );
+    }
+
+    #[test]
+    fn test_same_hash_different_workchain() {
+        let masterchain = Address::masterchain(&[0x33; 32]);
+        let basechain = Address::basechain(&[0x33; 32]);
+
+        // case (1): same hash, different workchains
+        assert!(masterchain.same_hash_different_workchain(&basechain));
+        assert!(basechain.same_hash_different_workchain(&masterchain));
+        assert_ne!(masterchain, basechain);
+
+        // case (2): fully equal addresses
+        assert!(!basechain.same_hash_different_workchain(&basechain.clone()));
+
+        // case (3): different hashes
+        assert!(!masterchain.same_hash_different_workchain(&Address::basechain(&[0x44; 32])));
+    }
+
+    #[test]
+    fn test_encoder_result_try_from_string() {
+        let expected =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): owned standard base64
+        let result =
+            EncoderResult::try_from("kQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJl/8".to_owned())
+                .unwrap();
+        assert_eq!(result, expected);
+        assert!(result.is_bounceable());
+        assert!(!result.is_production());
+        assert_eq!(result.decoder, Base64Decoder::Standard);
+
+        // case (2): owned url safe base64
+        let result =
+            EncoderResult::try_from("UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz".to_owned())
+                .unwrap();
+        assert_eq!(result, expected);
+        assert!(!result.is_bounceable());
+        assert!(result.is_production());
+        assert_eq!(result.decoder, Base64Decoder::UrlSafe);
+
+        // case (3): owned raw address gets the canonical flags
+        let result = EncoderResult::try_from(
+            "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026".to_owned(),
+        )
+        .unwrap();
+        assert_eq!(result, expected);
+        assert!(result.is_bounceable());
+        assert!(result.is_production());
+        assert_eq!(result.to_base64(), expected.canonical());
+
+        // case (4): invalid input
+        assert!(EncoderResult::try_from("invalid").is_err());
+    }
+
+    #[test]
+    fn test_flip_alphabet() {
+        let standard = "kQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJl/8";
+        let url_safe = "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8";
+
+        // case (1): standard to url safe and back, the flags are preserved
+        assert_eq!(to_url_safe(standard).unwrap(), url_safe);
+        assert_eq!(to_standard(url_safe).unwrap(), standard);
+
+        // case (2): converting to the same alphabet is a no-op
+        assert_eq!(to_url_safe(url_safe).unwrap(), url_safe);
+        assert_eq!(to_standard(standard).unwrap(), standard);
+
+        // case (3): raw addresses are not accepted
+        assert!(
+            to_url_safe("0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_try_from_bytes() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+        let bytes = address
+            .to_raw_bytes(BASE64_URL_TESTNET_NON_BOUNCEABLE)
+            .unwrap();
+
+        // case (1): valid owned and borrowed buffers
+        assert_eq!(Address::try_from(bytes.to_vec()), Ok(address.clone()));
+        assert_eq!(Address::try_from(&bytes[..]), Ok(address));
+
+        // case (2): truncated buffer
+        let truncated = bytes[..35].to_vec();
+        assert_eq!(
+            Address::try_from(truncated.clone()),
+            Err(ParseError {
+                address: hex::encode(&truncated),
+                reason: "Invalid base64 address string: length of decoded bytes must be 36",
+                offset: None,
+            })
+        );
+
+        // case (3): broken checksum
+        let mut broken = bytes.to_vec();
+        broken[35] ^= 0xFF;
+        assert_eq!(
+            Address::try_from(broken.clone()),
+            Err(ParseError {
+                address: hex::encode(&broken),
+                reason: "Invalid base64 address string: CRC16 hashes do not match",
+                offset: Some(34),
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode_options() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): the default options match BASE64_URL_DEFAULT
+        let options = EncodeOptions::default();
+        assert_eq!(
+            address.to_base64_with_options(&options),
+            address.to_base64(BASE64_URL_DEFAULT)
+        );
+        assert_eq!(options.to_encoder().flags(), BASE64_URL_DEFAULT.flags());
+        assert_eq!(options.to_encoder().alphabet(), Base64Decoder::UrlSafe);
+
+        // case (2): custom options
+        let options = EncodeOptions {
+            url_safe: false,
+            bounceable: true,
+            production: false,
+        };
+        assert_eq!(
+            address.to_base64_with_options(&options),
+            "kQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJl/8"
+        );
+    }
+
+    #[test]
+    fn test_parse_lossy() {
+        // case (1): a mix of valid and invalid lines
+        let (addresses, errors) = parse_lossy([
+            "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+            "invalid",
+            "-1:3333333333333333333333333333333333333333333333333333333333333333",
+            "",
+        ]);
+
+        assert_eq!(
+            addresses,
+            vec![
+                Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap(),
+                Address::masterchain(&[0x33; 32]),
+            ]
+        );
+        assert_eq!(
+            errors
+                .iter()
+                .map(|err| err.address.as_str())
+                .collect::<Vec<_>>(),
+            vec!["invalid", ""]
+        );
+
+        // case (2): no input
+        let (addresses, errors) = parse_lossy(Vec::<String>::new());
+        assert!(addresses.is_empty());
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_encoder_result_describe() {
+        // case (1): every flag combination
+        for (address, description) in [
+            (
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+                "bounceable, mainnet",
+            ),
+            (
+                "UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz",
+                "non-bounceable, mainnet",
+            ),
+            (
+                "kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8",
+                "bounceable, testnet",
+            ),
+            (
+                "0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5",
+                "non-bounceable, testnet",
+            ),
+        ] {
+            let result = Address::from_base64(address, None).unwrap();
+
+            assert_eq!(result.describe(), description);
+            assert_eq!(result.to_base64(), address);
+        }
+    }
+
+    #[test]
+    fn test_compatible_alphabets() {
+        // case (1): standard only
+        assert_eq!(
+            Base64Decoder::compatible_alphabets("kQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJl/8"),
+            vec![Base64Decoder::Standard]
+        );
+
+        // case (2): url safe only
+        assert_eq!(
+            Base64Decoder::compatible_alphabets("kQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJl_8"),
+            vec![Base64Decoder::UrlSafe]
+        );
+
+        // case (3): alphanumeric strings are compatible with both alphabets
+        assert_eq!(
+            Base64Decoder::compatible_alphabets("Ef8zMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzM0vF"),
+            vec![Base64Decoder::Standard, Base64Decoder::UrlSafe]
+        );
+
+        // case (4): mixed or foreign characters
+        assert!(Base64Decoder::compatible_alphabets("ab+cd-ef").is_empty());
+        assert!(Base64Decoder::compatible_alphabets("ab=cd").is_empty());
+    }
+
+    #[test]
+    fn test_undersized_input() {
+        let bytes = Address::empty().to_raw_bytes(BASE64_URL_DEFAULT).unwrap();
+
+        // case (1): byte slices and vectors of every short length
+        for len in 0..36 {
+            let error = Address::try_from(&bytes[..len]).unwrap_err();
+            assert_eq!(
+                error.reason,
+                "Invalid base64 address string: length of decoded bytes must be 36"
+            );
+
+            assert_eq!(Address::try_from(bytes[..len].to_vec()), Err(error));
+        }
+
+        // case (2): short Base64 strings
+        let encoded = Address::empty().to_base64(BASE64_URL_DEFAULT);
+
+        for len in 0..48 {
+            assert!(Address::from_base64(&encoded[..len], None).is_err());
+            assert!(Address::from_base64_bytes(&encoded.as_bytes()[..len], None).is_err());
+            assert!(Address::from_base64_extended(&encoded[..len], None).is_err());
+            assert!(
+                Address::from_base64_with_codec(&encoded[..len], &Base64Decoder::UrlSafe).is_err()
+            );
         }
 
-        {
-            let raw_address = "0:][p][;cr3244";
-            let address = Address::from_raw_address(raw_address);
+        // case (3): empty input
+        assert_eq!(
+            Address::from_base64_with_codec("", &Base64Decoder::UrlSafe),
+            Err(ParseError {
+                address: "".to_owned(),
+                reason: "Invalid base64 address string: length of decoded bytes must be 36",
+                offset: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_write_base64() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+        let mut out = [0u8; 48];
+
+        // case (1): the buffer matches to_base64 for every encoder
+        for encoder in [
+            BASE64_STD_DEFAULT,
+            BASE64_URL_DEFAULT,
+            BASE64_STD_TESTNET,
+            BASE64_URL_TESTNET_NON_BOUNCEABLE,
+        ] {
+            address.write_base64(encoder, &mut out).unwrap();
+            assert_eq!(&out, address.to_base64(encoder).as_bytes());
+        }
+
+        // case (2): the extended encoder doesn't fit
+        let encoder = Base64Encoder::Extended {
+            alphabet: Base64Decoder::UrlSafe,
+            bounceable: true,
+            production: true,
+        };
+        assert_eq!(
+            address.write_base64(encoder, &mut out),
+            Err(ParseError {
+                address: address.to_raw_address(),
+                reason: "Invalid encoder: extended addresses don't fit in 48 bytes",
+                offset: None,
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "diesel")]
+    fn test_diesel() {
+        use diesel::deserialize::FromSql;
+        use diesel::pg::{Pg, PgMetadataLookup, PgTypeMetadata, PgValue};
+        use diesel::query_builder::bind_collector::RawBytesBindCollector;
+        use diesel::query_builder::BindCollector;
+        use diesel::sql_types::Text;
+        use std::num::NonZeroU32;
+
+        // A backend without a connection, built-in types never need a lookup
+        struct MockLookup;
+
+        impl PgMetadataLookup for MockLookup {
+            fn lookup_type(&mut self, _: &str, _: Option<&str>) -> PgTypeMetadata {
+                unreachable!("TEXT is a built-in type")
+            }
+        }
+
+        let address = "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+            .parse::<Address>()
+            .unwrap();
+
+        // case (1): serialization
+        let mut collector = RawBytesBindCollector::<Pg>::new();
+        collector
+            .push_bound_value::<Text, Address>(&address, &mut MockLookup)
+            .unwrap();
+
+        let stored = collector.binds[0].clone().unwrap();
+        assert_eq!(stored, b"EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2");
+
+        // case (2): deserialization
+        let text_oid = NonZeroU32::new(25).unwrap();
+
+        let loaded =
+            <Address as FromSql<Text, Pg>>::from_sql(PgValue::new(&stored, &text_oid)).unwrap();
+        assert_eq!(loaded, address);
+
+        // case (3): parse failures are reported as deserialization errors
+        let invalid =
+            <Address as FromSql<Text, Pg>>::from_sql(PgValue::new(b"bad string", &text_oid))
+                .unwrap_err();
+        assert_eq!(
+            invalid.downcast_ref::<ParseError>().unwrap().address,
+            "bad string"
+        );
+    }
+
+    #[test]
+    fn test_from_raw_address_strict() {
+        let canonical = "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026";
+        let expected = Address::from_raw_address(canonical).unwrap();
+
+        // case (1): the canonical form is accepted by both parsers
+        assert_eq!(
+            Address::from_raw_address_strict(canonical),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            Address::from_raw_address_strict(&Address::masterchain(&[0x33; 32]).to_raw_address()),
+            Ok(Address::masterchain(&[0x33; 32]))
+        );
 
+        // case (2): an uppercase hash part is only accepted by the lenient parser
+        let uppercase = "0:0E97797708411C29A3CB1F3F810EF4F83F41D990838F7F93CE7082C4FF9AA026";
+        assert_eq!(Address::from_raw_address(uppercase), Ok(expected));
+        assert_eq!(
+            Address::from_raw_address_strict(uppercase),
+            Err(ParseError {
+                address: uppercase.to_owned(),
+                reason: "Invalid raw address string: hash part must be lowercase",
+                offset: None,
+            })
+        );
+
+        // case (3): non-canonical workchains
+        for (address, reason) in [
+            (
+                " 0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+                "Invalid raw address string: workchain number has leading whitespace",
+            ),
+            (
+                "+0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+                "Invalid raw address string: workchain number has a plus sign",
+            ),
+            (
+                "00:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+                "Invalid raw address string: workchain number is not canonical",
+            ),
+            (
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026 ",
+                "Invalid raw address string: hash part is not canonical",
+            ),
+        ] {
+            assert!(Address::from_raw_address(address).is_ok());
             assert_eq!(
-                address,
+                Address::from_raw_address_strict(address),
                 Err(ParseError {
-                    address: raw_address.to_owned(),
-                    reason: "Invalid raw address string: failed to decode hash part",
+                    address: address.to_owned(),
+                    reason,
+                    offset: None,
                 })
             );
         }
+    }
 
-        {
-            let raw_address = "0:ABCDE012";
-            let address = Address::from_raw_address(raw_address);
+    #[test]
+    fn test_friendly_common_prefix_len() {
+        let a = Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+        let b = Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJ_RX").unwrap();
+
+        // case (1): addresses differing in the last byte of the hash part
+        assert_eq!(a.friendly_common_prefix_len(&b, BASE64_URL_DEFAULT), 45);
+        assert_eq!(b.friendly_common_prefix_len(&a, BASE64_URL_DEFAULT), 45);
+
+        // case (2): the same address shares every character
+        assert_eq!(a.friendly_common_prefix_len(&a, BASE64_URL_DEFAULT), 48);
+
+        // case (3): the tag and workchain bytes share the first character only (EQ vs Ef)
+        let masterchain = Address::masterchain(a.get_hash_part());
+        assert_eq!(
+            a.friendly_common_prefix_len(&masterchain, BASE64_URL_DEFAULT),
+            1
+        );
+    }
+
+    #[test]
+    fn test_into_parts() {
+        // case (1): round trip
+        for address in [
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap(),
+            Address::masterchain(&[0x33; 32]),
+            Address::new(1000, &[0xFF; 32]),
+        ] {
+            let (workchain, hash_part) = address.clone().into_parts();
+
+            assert_eq!(workchain, address.get_workchain());
+            assert_eq!(&hash_part, address.get_hash_part());
+            assert_eq!(Address::from_parts((workchain, hash_part)), address);
+        }
+    }
+
+    #[test]
+    fn test_default_encoder() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): url safe basechain address
+        let encoder = address.default_encoder(true);
+        assert_eq!(
+            address.to_base64(encoder),
+            address.to_base64(BASE64_URL_DEFAULT)
+        );
+        assert_eq!(encoder.flags(), BASE64_URL_DEFAULT.flags());
+        assert_eq!(encoder.alphabet(), Base64Decoder::UrlSafe);
+
+        // case (2): standard alphabet
+        let encoder = address.default_encoder(false);
+        assert_eq!(
+            address.to_base64(encoder),
+            address.to_base64(BASE64_STD_DEFAULT)
+        );
+        assert_eq!(encoder.alphabet(), Base64Decoder::Standard);
+    }
+
+    #[test]
+    fn test_normalize_addresses() {
+        let addresses = vec![
+            "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+            "kQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJl/8",
+            "invalid",
+            "0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5",
+        ];
+
+        // case (1): mixed formats
+        let normalized = addresses.iter().normalize_addresses().collect::<Vec<_>>();
+
+        assert_eq!(normalized.len(), 4);
+        assert_eq!(
+            normalized[0],
+            Ok("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2".to_owned())
+        );
+        assert_eq!(normalized[1], normalized[0]);
+        assert!(normalized[2].is_err());
+        assert_eq!(normalized[3], normalized[0]);
+
+        // case (2): filter_map ergonomics
+        let valid = addresses
+            .into_iter()
+            .normalize_addresses()
+            .filter_map(Result::ok)
+            .collect::<HashSet<_>>();
+        assert_eq!(valid.len(), 1);
+    }
+
+    #[test]
+    fn test_crc_is_valid() {
+        // case (1): valid addresses
+        assert_eq!(
+            crc_is_valid("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"),
+            Ok(true)
+        );
+        assert_eq!(
+            crc_is_valid("kQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJl/8"),
+            Ok(true)
+        );
+
+        // case (2): a single corrupted character
+        assert_eq!(
+            crc_is_valid("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR3"),
+            Ok(false)
+        );
+        assert_eq!(
+            crc_is_valid("EQAOl3l3CEEcKaPLHz-CDvT4P0HZkIOPf5POcILE_5qgJuR2"),
+            Ok(false)
+        );
+
+        // case (3): the flags are not validated
+        let mut bytes = Address::empty().to_raw_bytes(BASE64_URL_DEFAULT).unwrap();
+        bytes[0] = 0x00;
+        let crc = crc16(&bytes[0..34]);
+        bytes[34] = (crc >> 8) as u8;
+        bytes[35] = crc as u8;
+        let address = BASE64_URL_SAFE_NO_PAD.encode(bytes);
+
+        assert_eq!(crc_is_valid(&address), Ok(true));
+        assert!(Address::from_base64(&address, None).is_err());
+
+        // case (4): undecodable strings
+        assert_eq!(
+            crc_is_valid("EQAOl3l3"),
+            Err(ParseError {
+                address: "EQAOl3l3".to_owned(),
+                reason: "Invalid base64 address string: length must be 48 characters",
+                offset: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_testnet_non_bounceable() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): url safe
+        let encoded = address.to_testnet_non_bounceable(true);
+        assert!(encoded.starts_with("0Q"));
+        assert_eq!(encoded, "0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5");
+
+        // case (2): standard
+        let encoded = address.to_testnet_non_bounceable(false);
+        assert!(encoded.starts_with("0Q"));
+        assert_eq!(encoded, "0QAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJgI5");
+
+        let result = Address::from_base64(&encoded, None).unwrap();
+        assert!(!result.is_bounceable());
+        assert!(!result.is_production());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_with_modules() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Raw {
+            #[serde(with = "crate::serde_raw")]
+            address: Address,
+        }
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct FriendlyUrl {
+            #[serde(with = "crate::serde_friendly_url")]
+            address: Address,
+        }
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct FriendlyStd {
+            #[serde(with = "crate::serde_friendly_std")]
+            address: Address,
+        }
+
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): raw
+        let json = serde_json::to_string(&Raw {
+            address: address.clone(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"address":"0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"}"#
+        );
+        assert_eq!(serde_json::from_str::<Raw>(&json).unwrap().address, address);
+
+        // case (2): url safe
+        let json = serde_json::to_string(&FriendlyUrl {
+            address: address.clone(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"address":"EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<FriendlyUrl>(&json).unwrap().address,
+            address
+        );
+
+        // case (3): standard
+        let json = serde_json::to_string(&FriendlyStd {
+            address: address.clone(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"address":"EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<FriendlyStd>(&json).unwrap().address,
+            address
+        );
+
+        // case (4): a value in another representation is rejected
+        assert!(serde_json::from_str::<Raw>(
+            r#"{"address":"EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<FriendlyStd>(
+            r#"{"address":"EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_from_ton_uri() {
+        let expected =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): bare URI
+        let (address, params) =
+            from_ton_uri("ton://transfer/EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2")
+                .unwrap();
+        assert_eq!(address, expected);
+        assert!(params.is_empty());
+
+        // case (2): amount and text parameters
+        let (address, params) = from_ton_uri(
+            "ton://transfer/UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz?amount=1000000000&text=hello%20world",
+        )
+        .unwrap();
+        assert_eq!(address, expected);
+        assert_eq!(
+            params,
+            BTreeMap::from([
+                ("amount".to_owned(), "1000000000".to_owned()),
+                ("text".to_owned(), "hello world".to_owned()),
+            ])
+        );
+
+        // case (3): form encoded spaces, an escaped plus is kept
+        let (_, params) = from_ton_uri(
+            "ton://transfer/EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2?text=hello+world%2B1",
+        )
+        .unwrap();
+        assert_eq!(params["text"], "hello world+1");
+
+        // case (4): other schemes and actions
+        assert_eq!(
+            from_ton_uri("https://transfer/EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"),
+            Err(ParseError {
+                address: "https://transfer/EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+                    .to_owned(),
+                reason: "Invalid ton URI: scheme must be ton://",
+                offset: None,
+            })
+        );
+        assert_eq!(
+            from_ton_uri("ton://stake/EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2")
+                .unwrap_err()
+                .reason,
+            "Invalid ton URI: expected ton://transfer/<address>"
+        );
+
+        // case (5): invalid address and malformed parameters
+        assert!(from_ton_uri("ton://transfer/invalid").is_err());
+        assert_eq!(
+            from_ton_uri(
+                "ton://transfer/EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2?text=%zz"
+            )
+            .unwrap_err()
+            .reason,
+            "Invalid ton URI: malformed percent-encoding"
+        );
+    }
+
+    #[test]
+    fn test_to_base64_bytes() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): the bytes match to_base64 for every encoder
+        for encoder in [
+            BASE64_STD_DEFAULT,
+            BASE64_URL_DEFAULT,
+            BASE64_STD_TESTNET_NON_BOUNCEABLE,
+            Base64Encoder::Extended {
+                alphabet: Base64Decoder::UrlSafe,
+                bounceable: true,
+                production: true,
+            },
+        ] {
+            assert_eq!(
+                address.to_base64_bytes(encoder),
+                address.to_base64(encoder).into_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn test_equals_raw() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): matching raw strings
+        assert_eq!(
+            address
+                .equals_raw("0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"),
+            Ok(true)
+        );
+        assert_eq!(
+            address
+                .equals_raw("0:0E97797708411C29A3CB1F3F810EF4F83F41D990838F7F93CE7082C4FF9AA026"),
+            Ok(true)
+        );
+
+        // case (2): non-matching raw strings
+        assert_eq!(
+            address
+                .equals_raw("-1:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"),
+            Ok(false)
+        );
+
+        // case (3): not a raw address
+        assert_eq!(
+            address.equals_raw("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"),
+            Err(ParseError {
+                address: "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2".to_owned(),
+                reason: "Invalid raw address string: wrong address format",
+                offset: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_base64_checked() {
+        // case (1): the null address
+        let empty = Address::empty();
+        assert!(empty.is_zero());
+
+        let warning = empty.to_base64_checked(BASE64_URL_DEFAULT).unwrap_err();
+        assert_eq!(
+            warning,
+            AddressWarning::NullAddress {
+                encoded: empty.to_base64(BASE64_URL_DEFAULT),
+            }
+        );
+        assert_eq!(
+            warning.to_string(),
+            format!(
+                "Warning encoding TON address: {} is the null address",
+                empty.to_base64(BASE64_URL_DEFAULT)
+            )
+        );
+        assert!(Address::masterchain(&[0; 32])
+            .to_base64_checked(BASE64_URL_DEFAULT)
+            .is_err());
+
+        // case (2): a normal address
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+        assert!(!address.is_zero());
+        assert_eq!(
+            address.to_base64_checked(BASE64_URL_DEFAULT),
+            Ok(address.to_base64(BASE64_URL_DEFAULT))
+        );
+    }
+
+    #[test]
+    fn test_hashes_to_friendly() {
+        let hash_part: HashPart =
+            hex::decode("0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026")
+                .unwrap()
+                .try_into()
+                .unwrap();
+
+        // case (1): basechain hashes
+        assert_eq!(
+            hashes_to_friendly(vec![hash_part, [0x33; 32]], 0, BASE64_URL_DEFAULT),
+            vec![
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2".to_owned(),
+                Address::basechain(&[0x33; 32]).to_base64(BASE64_URL_DEFAULT),
+            ]
+        );
+
+        // case (2): masterchain hashes
+        assert_eq!(
+            hashes_to_friendly([[0x33; 32]], -1, BASE64_URL_DEFAULT),
+            vec!["Ef8zMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzM0vF".to_owned()]
+        );
+
+        // case (3): no hashes
+        assert!(hashes_to_friendly([], 0, BASE64_URL_DEFAULT).is_empty());
+    }
+
+    #[test]
+    fn test_payload_bytes() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): the CRC of the payload is stored at the end of the raw bytes
+        for encoder in [
+            BASE64_URL_DEFAULT,
+            BASE64_STD_TESTNET,
+            BASE64_URL_TESTNET_NON_BOUNCEABLE,
+        ] {
+            let payload = address.payload_bytes(encoder).unwrap();
+            let raw = address.to_raw_bytes(encoder).unwrap();
+
+            assert_eq!(payload, raw[0..34]);
+            assert_eq!(crc16(&payload).to_be_bytes(), raw[34..36]);
+            assert_eq!(crc16(&payload), address.checksum(encoder).unwrap());
+        }
+
+        // case (2): layout
+        let payload = address.payload_bytes(BASE64_URL_DEFAULT).unwrap();
+        assert_eq!(payload[0], 0x11);
+        assert_eq!(payload[1], 0x00);
+        assert_eq!(&payload[2..], address.get_hash_part());
+    }
+
+    #[test]
+    fn test_from_scanned() {
+        let expected =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): scheme-prefixed payloads
+        for scanned in [
+            "ton://transfer/EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+            "TON://TRANSFER/EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+            "ton://EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+            "ton://transfer/EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2?amount=1",
+        ] {
+            assert_eq!(from_scanned(scanned), Ok(expected.clone()));
+        }
+
+        // case (2): bare payloads
+        assert_eq!(
+            from_scanned(" EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2\n"),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            from_scanned("0:0E97797708411C29A3CB1F3F810EF4F83F41D990838F7F93CE7082C4FF9AA026"),
+            Ok(expected)
+        );
+
+        // case (3): an uppercased base64 payload
+        assert_eq!(
+            from_scanned("TON://TRANSFER/EQAOL3L3CEECKAPLHZ-BDVT4P0HZKIOPF5POCILE_5QGJUR2"),
+            Err(ParseError {
+                address: "TON://TRANSFER/EQAOL3L3CEECKAPLHZ-BDVT4P0HZKIOPF5POCILE_5QGJUR2"
+                    .to_owned(),
+                reason: "Invalid scanned address: the base64 payload was uppercased",
+                offset: None,
+            })
+        );
+
+        // case (4): other errors keep their reason
+        assert_eq!(
+            from_scanned("ton://transfer/invalid").unwrap_err().address,
+            "ton://transfer/invalid"
+        );
+    }
+
+    #[cfg(feature = "bloom")]
+    #[test]
+    fn test_address_bloom() {
+        let address = |i: u32| {
+            let mut hash_part = [0u8; 32];
+            hash_part[..4].clone_from_slice(&i.to_be_bytes());
+
+            Address::basechain(&hash_part)
+        };
+
+        let mut bloom = AddressBloom::new(100, 0.01);
+
+        // case (1): inserted addresses are always found, in any form
+        for i in 0..100 {
+            bloom.insert(&address(i));
+        }
+
+        for i in 0..100 {
+            assert!(bloom.contains(&address(i)));
+        }
+
+        let encoded = address(7).to_base64(BASE64_URL_TESTNET_NON_BOUNCEABLE);
+        assert!(bloom.contains(&encoded.parse::<Address>().unwrap()));
+
+        // case (2): other addresses are usually not found
+        let false_positives = (100..10_100)
+            .filter(|i| bloom.contains(&address(*i)))
+            .count();
+        assert!(false_positives < 500, "{false_positives} false positives");
+
+        // case (3): an empty filter contains nothing
+        assert!(!AddressBloom::new(10, 0.01).contains(&address(0)));
+    }
+
+    #[test]
+    fn test_from_base64_prefix() {
+        let expected =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): pure address
+        let (result, rest) =
+            Address::from_base64_prefix("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2")
+                .unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(rest, "");
+
+        // case (2): address followed by extra characters
+        let (result, rest) =
+            Address::from_base64_prefix("0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5:memo=42")
+                .unwrap();
+        assert_eq!(result, expected);
+        assert!(!result.is_bounceable());
+        assert_eq!(rest, ":memo=42");
+
+        // case (3): too short or shifted input
+        assert_eq!(
+            Address::from_base64_prefix("EQAOl3l3"),
+            Err(ParseError {
+                address: "EQAOl3l3".to_owned(),
+                reason: "Invalid base64 address string: length must be 48 characters",
+                offset: None,
+            })
+        );
+        assert!(
+            Address::from_base64_prefix(" EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2")
+                .is_err()
+        );
+
+        // case (4): a multi-byte character crossing the 48th byte
+        assert_eq!(
+            Address::from_base64_prefix("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuRé"),
+            Err(ParseError {
+                address: "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuRé".to_owned(),
+                reason: "Invalid base64 address string: address contains non-ASCII characters",
+                offset: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_short_display() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): typical values
+        assert_eq!(
+            address.to_short_display(BASE64_URL_DEFAULT, 4, 4),
+            "EQAO…JuR2"
+        );
+        assert_eq!(
+            address.to_short_display(BASE64_URL_DEFAULT, 6, 0),
+            "EQAOl3…"
+        );
+        assert_eq!(
+            address.to_short_display(BASE64_URL_DEFAULT, 0, 6),
+            "…qgJuR2"
+        );
+        assert_eq!(
+            address.to_short_display(BASE64_URL_DEFAULT, 24, 23),
+            "EQAOl3l3CEEcKaPLHz-BDvT4…0HZkIOPf5POcILE_5qgJuR2"
+        );
 
+        // case (2): overlapping values are clamped to the full string
+        for (head, tail) in [(24, 24), (40, 40), (usize::MAX, 1)] {
             assert_eq!(
-                address,
-                Err(ParseError {
-                    address: raw_address.to_owned(),
-                    reason: "Invalid raw address string: hash part length must be 32 bytes",
-                })
+                address.to_short_display(BASE64_URL_DEFAULT, head, tail),
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
             );
         }
     }
 
     #[test]
-    fn test_from_base64() {
-        // main case (1): [bounceable] + [production] + [encoder guessing]
-        {
-            let result =
-                Address::from_base64("EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR", None)
-                    .unwrap();
+    fn test_tagged_payload() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
 
-            // Encoder result
-            assert_eq!(result.is_bounceable(), true);
-            assert_eq!(result.is_production(), true);
-            assert_eq!(result.decoder, Base64Decoder::UrlSafe);
+        // case (1): round trip with every flag combination
+        for encoder in [
+            BASE64_URL_DEFAULT,
+            BASE64_URL_TESTNET,
+            BASE64_STD_TESTNET_NON_BOUNCEABLE,
+            Base64Encoder::url_safe(AddressFlags::empty()),
+        ] {
+            let payload = address.payload_bytes(encoder).unwrap();
+            assert_eq!(payload, address.to_raw_bytes(encoder).unwrap()[0..34]);
 
-            // Address
-            assert_eq!(result.address.get_workchain(), 0);
             assert_eq!(
-                result.address.get_hash_part(),
-                &[
-                    228, 217, 84, 239, 159, 78, 18, 80, 162, 107, 91, 186, 215, 106, 28, 221, 23,
-                    207, 208, 139, 171, 173, 111, 76, 35, 227, 114, 39, 10, 239, 111, 118
-                ]
+                Address::from_tagged_payload(&payload),
+                Ok((address.clone(), encoder.flags()))
             );
         }
 
-        // main case (2): [non bounceable] + [production] + [encoder guessing]
-        {
-            let result =
-                Address::from_base64("UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t", None)
-                    .unwrap();
+        // case (2): invalid tag byte
+        let mut payload = address.payload_bytes(BASE64_URL_DEFAULT).unwrap();
+        payload[0] = 0x00;
 
-            // Encoder result
-            assert_eq!(result.is_bounceable(), false);
-            assert_eq!(result.is_production(), true);
-            assert_eq!(result.decoder, Base64Decoder::Standard);
+        assert_eq!(
+            Address::from_tagged_payload(&payload),
+            Err(ParseError {
+                address: hex::encode(payload),
+                reason: "Invalid base64 address string: invalid flag",
+                offset: Some(0),
+            })
+        );
+    }
 
-            // Address
-            assert_eq!(result.address.get_workchain(), 0);
-            assert_eq!(
-                result.address.get_hash_part(),
-                &[
-                    22u8, 204, 66, 156, 118, 124, 164, 189, 119, 212, 54, 139, 170, 117, 46, 182,
-                    182, 250, 233, 223, 102, 194, 198, 226, 146, 233, 228, 43, 75, 162, 18, 129
-                ]
-            );
-        }
+    #[test]
+    fn test_masterchain_friendly_forms() {
+        let expected = Address::from_raw_address(
+            "-1:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+        )
+        .unwrap();
 
-        // error case (1): bad length
-        {
-            let result = Address::from_base64("bad length", None);
-            assert_eq!(
-                result,
-                Err(ParseError {
-                    address: "bad length".to_owned(),
-                    reason: "Invalid base64 address string: length must be 48 characters"
-                })
-            );
-        }
+        // case (1): every masterchain prefix in both alphabets
+        for (url_safe, standard, bounceable, production) in [
+            (
+                "Ef8Ol3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJhs-",
+                "Ef8Ol3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJhs+",
+                true,
+                true,
+            ),
+            (
+                "Uf8Ol3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJkb7",
+                "Uf8Ol3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJkb7",
+                false,
+                true,
+            ),
+            (
+                "kf8Ol3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJqC0",
+                "kf8Ol3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJqC0",
+                true,
+                false,
+            ),
+            (
+                "0f8Ol3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJv1x",
+                "0f8Ol3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJv1x",
+                false,
+                false,
+            ),
+        ] {
+            for encoded in [url_safe, standard] {
+                let result = Address::from_base64(encoded, None).unwrap();
 
-        // error case (2): byte length
-        {
-            let result =
-                Address::from_base64("EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrRIyM", None);
-            assert_eq!(
-                result,
-                Err(ParseError {
-                    address: "EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrRIyM".to_owned(),
-                    reason: "Invalid base64 address string: length must be 48 characters"
-                })
-            );
-        }
+                assert_eq!(result.address, expected);
+                assert_eq!(result.address.get_workchain(), -1);
+                assert_eq!(result.is_bounceable(), bounceable);
+                assert_eq!(result.is_production(), production);
+                assert_eq!(result.to_base64(), encoded);
+            }
 
-        // error case (3): invalid flag
-        {
-            let result =
-                Address::from_base64("VQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR", None);
-            assert_eq!(
-                result,
-                Err(ParseError {
-                    address: "VQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR".to_owned(),
-                    reason: "Invalid base64 address string: invalid flag"
-                })
-            );
+            // case (2): encoding gives back the same prefix
+            let flags = AddressFlags::from_bools(bounceable, production);
+            assert_eq!(expected.to_base64(Base64Encoder::url_safe(flags)), url_safe);
+            assert_eq!(expected.to_base64(Base64Encoder::standard(flags)), standard);
         }
+    }
 
-        // error case (3): bad CRC16
-        {
-            let result =
-                Address::from_base64("EQDkqlTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR", None);
+    #[test]
+    fn test_account_id_hex_0x() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): with the prefix
+        let hex = address.account_id_hex_0x();
+        assert_eq!(
+            hex,
+            "0x0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+        );
+        assert_eq!(Address::from_account_id_hex(0, &hex), Ok(address.clone()));
+
+        // case (2): without the prefix
+        assert_eq!(
+            Address::from_account_id_hex(0, &hex[2..]),
+            Ok(address.clone())
+        );
+        assert_eq!(
+            Address::from_account_id_hex(-1, &hex[2..]),
+            Ok(Address::masterchain(address.get_hash_part()))
+        );
+
+        // case (3): invalid account ids
+        for invalid in ["", "0x", "0x0e97", &format!("{hex}00"), "0xzz"] {
             assert_eq!(
-                result,
+                Address::from_account_id_hex(0, invalid),
                 Err(ParseError {
-                    address: "EQDkqlTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR".to_owned(),
-                    reason: "Invalid base64 address string: CRC16 hashes do not match"
+                    address: invalid.to_owned(),
+                    reason: "Invalid account id string: expected 64 hex digits",
+                    offset: None,
                 })
             );
         }
     }
 
     #[test]
-    fn test_compare_addresses() {
-        // case (1): same addresses
-        {
-            let address1 =
-                Address::from_base64("UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t", None)
-                    .unwrap()
-                    .address;
+    fn test_canonical_address() {
+        use std::collections::HashMap;
 
-            let address2 =
-                Address::from_base64("UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t", None)
-                    .unwrap()
-                    .address;
+        // case (1): map key across mixed formats
+        let mut balances = HashMap::new();
 
-            assert_eq!(address1, address2);
+        for (address, amount) in [
+            ("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2", 1),
+            (
+                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+                2,
+            ),
+            ("kQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJl/8", 3),
+            ("Ef8zMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzM0vF", 4),
+        ] {
+            *balances
+                .entry(address.parse::<CanonicalAddress>().unwrap())
+                .or_insert(0) += amount;
         }
 
-        // case (2): not same
-        {
-            let address1 =
-                Address::from_base64("UQAWzEKcdnykvXfUNouqdS62tvrp32bCxuKS6eQrS6ISgZ8t", None)
-                    .unwrap()
-                    .address;
+        assert_eq!(balances.len(), 2);
+        assert_eq!(
+            balances[&"UQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJrmz"
+                .parse::<CanonicalAddress>()
+                .unwrap()],
+            6
+        );
 
-            let address2 =
-                Address::from_base64("EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR", None)
-                    .unwrap()
-                    .address;
+        // case (2): display
+        let key = "0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5"
+            .parse::<CanonicalAddress>()
+            .unwrap();
+        assert_eq!(
+            key.to_string(),
+            "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+        );
 
-            assert_ne!(address1, address2);
+        // case (3): ordered by workchain, then by hash part
+        let mut keys = vec![
+            CanonicalAddress(Address::basechain(&[0x33; 32])),
+            CanonicalAddress(Address::basechain(&[0x11; 32])),
+            CanonicalAddress(Address::masterchain(&[0x44; 32])),
+        ];
+        keys.sort();
+
+        assert_eq!(
+            keys,
+            vec![
+                CanonicalAddress(Address::masterchain(&[0x44; 32])),
+                CanonicalAddress(Address::basechain(&[0x11; 32])),
+                CanonicalAddress(Address::basechain(&[0x33; 32])),
+            ]
+        );
+
+        // case (4): invalid input
+        assert!("invalid".parse::<CanonicalAddress>().is_err());
+    }
+
+    #[test]
+    fn test_friendly_aliases() {
+        let address =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): eight friendly forms plus the raw form
+        let aliases = address.friendly_aliases().collect::<Vec<_>>();
+        assert_eq!(aliases.len(), 9);
+        assert_eq!(aliases.iter().collect::<HashSet<_>>().len(), 9);
+
+        assert!(aliases.contains(&"EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2".to_owned()));
+        assert!(aliases.contains(&"EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2".to_owned()));
+        assert_eq!(
+            aliases.last().unwrap(),
+            "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
+        );
+
+        // case (2): every alias points to the same account
+        for alias in aliases {
+            assert_eq!(alias.parse::<Address>().unwrap(), address);
         }
     }
 
     #[test]
-    fn test_multi_converts() {
-        // case (1): from base64 url safe
-        {
-            let addr = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
-                .parse::<Address>()
-                .unwrap();
+    fn test_from_base64_alphabet_mismatch() {
+        // case (1): standard alphabet forced on a url safe only string
+        assert_eq!(
+            Address::from_base64(
+                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2",
+                Some(Base64Decoder::Standard)
+            ),
+            Err(ParseError {
+                address: "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2".to_owned(),
+                reason: "Invalid base64 address string: alphabet mismatch",
+                offset: None,
+            })
+        );
 
-            assert_eq!(
-                addr.to_raw_address(),
-                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
-            );
-            assert_eq!(
-                addr.to_base64(BASE64_STD_DEFAULT),
-                "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2"
-            );
-            assert_eq!(
-                addr.to_base64(BASE64_URL_DEFAULT),
-                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
-            );
-            assert_eq!(
-                addr.to_string(),
-                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
-            );
+        // case (2): url safe alphabet forced on a standard only string
+        assert_eq!(
+            Address::from_base64(
+                "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2",
+                Some(Base64Decoder::UrlSafe)
+            )
+            .unwrap_err()
+            .reason,
+            "Invalid base64 address string: alphabet mismatch"
+        );
+
+        // case (3): alphanumeric strings fit both alphabets
+        for alphabet in [Base64Decoder::Standard, Base64Decoder::UrlSafe] {
+            assert!(Address::from_base64(
+                "Ef8zMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzM0vF",
+                Some(alphabet)
+            )
+            .is_ok());
         }
+    }
 
-        // case (2): from base64 url std
-        {
-            let addr = "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2"
-                .parse::<Address>()
-                .unwrap();
+    #[test]
+    fn test_from_raw_parts() {
+        const ELECTOR: Address = Address::from_raw_parts(-1, [0x33; 32]);
+        static BASECHAIN_ZERO: Address = Address::from_raw_parts(0, [0; 32]);
 
-            assert_eq!(
-                addr.to_raw_address(),
-                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
-            );
-            assert_eq!(
-                addr.to_base64(BASE64_STD_DEFAULT),
-                "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2"
-            );
-            assert_eq!(
-                addr.to_base64(BASE64_URL_DEFAULT),
-                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
-            );
-            assert_eq!(
-                addr.to_string(),
-                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
-            );
+        // case (1): const and static bindings match the runtime-parsed addresses
+        assert_eq!(
+            ELECTOR,
+            Address::from_str("Ef8zMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzM0vF").unwrap()
+        );
+        assert_eq!(BASECHAIN_ZERO, Address::empty());
+    }
+
+    #[test]
+    fn test_diff_kind() {
+        let url_safe = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2";
+
+        // case (1): every category
+        for (other, expected) in [
+            (url_safe, AddressDiff::Identical),
+            (
+                "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2",
+                AddressDiff::AlphabetOnly,
+            ),
+            (
+                "0QAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJgI5",
+                AddressDiff::FlagsOnly,
+            ),
+            (
+                "kQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJl/8",
+                AddressDiff::AlphabetAndFlags,
+            ),
+            (
+                "Ef8zMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzM0vF",
+                AddressDiff::DifferentAccount,
+            ),
+        ] {
+            assert_eq!(diff_kind(url_safe, other), Ok(expected));
+            assert_eq!(diff_kind(other, url_safe), Ok(expected));
         }
 
-        // case (2): from raw address
-        {
-            let addr = "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
-                .parse::<Address>()
-                .unwrap();
+        // case (2): alphanumeric addresses fit both alphabets
+        let masterchain = Address::masterchain(&[0x33; 32]);
+        assert_eq!(
+            diff_kind(
+                &masterchain.to_base64(BASE64_STD_DEFAULT),
+                &masterchain.to_base64(BASE64_URL_TESTNET)
+            ),
+            Ok(AddressDiff::FlagsOnly)
+        );
 
+        // case (3): invalid input
+        assert!(diff_kind(url_safe, "invalid").is_err());
+    }
+
+    #[test]
+    fn test_from_hash_hex() {
+        let expected =
+            Address::from_str("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2").unwrap();
+
+        // case (1): valid 64 hex digits
+        assert_eq!(
+            Address::from_hash_hex(
+                "0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+                0
+            ),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            Address::from_hash_hex(
+                " 0E97797708411C29A3CB1F3F810EF4F83F41D990838F7F93CE7082C4FF9AA026\n",
+                -1
+            ),
+            Ok(Address::masterchain(expected.get_hash_part()))
+        );
+
+        // case (2): wrong length
+        for invalid in [
+            "0e97",
+            "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+        ] {
             assert_eq!(
-                addr.to_raw_address(),
-                "0:0e97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026"
-            );
-            assert_eq!(
-                addr.to_base64(BASE64_STD_DEFAULT),
-                "EQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJuR2"
-            );
-            assert_eq!(
-                addr.to_base64(BASE64_URL_DEFAULT),
-                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
-            );
-            assert_eq!(
-                addr.to_string(),
-                "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2"
+                Address::from_hash_hex(invalid, 0),
+                Err(ParseError {
+                    address: invalid.to_owned(),
+                    reason: "Invalid hash string: length must be 64 hex digits",
+                    offset: None,
+                })
             );
         }
+
+        // case (3): invalid hex digits
+        assert_eq!(
+            Address::from_hash_hex(
+                "zz97797708411c29a3cb1f3f810ef4f83f41d990838f7f93ce7082c4ff9aa026",
+                0
+            )
+            .unwrap_err()
+            .reason,
+            "Invalid hash string: failed to decode hex digits"
+        );
+    }
+
+    #[test]
+    fn test_repair_crc() {
+        let valid = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuR2";
+
+        // case (1): a corrupted checksum is repaired
+        let corrupted = "EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJuAA";
+        assert!(Address::from_base64(corrupted, None).is_err());
+
+        let repaired = repair_crc(corrupted).unwrap();
+        assert_eq!(repaired, valid);
+        assert!(Address::from_base64(&repaired, None).is_ok());
+
+        // case (2): the alphabet is preserved
+        assert_eq!(
+            repair_crc("kQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJl/A").unwrap(),
+            "kQAOl3l3CEEcKaPLHz+BDvT4P0HZkIOPf5POcILE/5qgJl/8"
+        );
+
+        // case (3): a valid address is unchanged
+        assert_eq!(repair_crc(valid).unwrap(), valid);
+
+        // case (4): a corrupted payload yields a different account
+        let repaired = repair_crc("EQAOl3l3CEEcKaPLHz-BDvT4P0HZkIOPf5POcILE_5qgJ-R2").unwrap();
+        assert_ne!(
+            Address::from_base64(&repaired, None).unwrap().address,
+            Address::from_base64(valid, None).unwrap().address
+        );
+
+        // case (5): undecodable input
+        assert!(repair_crc("invalid").is_err());
     }
 }